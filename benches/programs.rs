@@ -0,0 +1,109 @@
+//! Timings of the interpretation of a few programs, run with `cargo bench`.
+//!
+//! Each program is parsed once and interpreted a number of times, and the least time taken is reported.
+
+use std::time::{Duration, Instant};
+
+use loxy_lib::interpreter::{Base, TreeWalker, config::Config};
+
+/// The number of times each program is interpreted.
+const RUNS: usize = 5;
+
+/// The greatest number of bytes of stack used by calls, within the stack of the main thread the programs are interpreted on.
+const MAX_STACK: usize = 4 << 20;
+
+const PROGRAMS: [(&str, &str); 6] = [
+    (
+        "fib",
+        "fun fib(n) { if (n < 2) { return n; } return fib(n - 1) + fib(n - 2); } fib(22);",
+    ),
+    (
+        "ackermann",
+        r#"
+fun ackermann(m, n) {
+    if (m == 0) { return n + 1; }
+    if (n == 0) { return ackermann(m - 1, 1); }
+    return ackermann(m - 1, ackermann(m, n - 1));
+}
+
+ackermann(2, 200);
+"#,
+    ),
+    (
+        "loop",
+        "var sum = 0; for (var i = 0; i < 200000; i += 1) { sum += i; }",
+    ),
+    (
+        "nested scopes",
+        r#"
+var total = 0;
+for (var i = 0; i < 300; i += 1) {
+    var a = i;
+    for (var j = 0; j < 300; j += 1) {
+        var b = j;
+        { total = total + a * b; }
+    }
+}
+"#,
+    ),
+    (
+        "closures",
+        r#"
+fun counter() {
+    var n = 0;
+    fun increment() { n += 1; return n; }
+    return increment;
+}
+
+var count = counter();
+for (var i = 0; i < 100000; i += 1) { count(); }
+"#,
+    ),
+    (
+        "strings",
+        r#"
+var words = ["alpha", "beta", "gamma", "a rather longer word, to be copied on each read"];
+var lengths = {};
+var count = 0;
+for (var i = 0; i < 50000; i += 1) {
+    var word = words[i % 4];
+    var copy = word;
+    if (copy == "beta") { count += 1; }
+    lengths[word] = copy.len();
+    var pair = word + "-" + copy;
+}
+"#,
+    ),
+];
+
+/// The least time taken to interpret `source`.
+fn time(source: &str) -> Duration {
+    let mut least = Duration::MAX;
+
+    for _ in 0..RUNS {
+        let mut walker = TreeWalker::with_config(Config {
+            max_stack: MAX_STACK,
+            ..Config::default()
+        });
+        walker.scan(source).expect("Scanner error");
+        let statements = walker.parse().expect("Parser error");
+
+        let mut base = Base::default();
+
+        let start = Instant::now();
+        for index in 0..statements {
+            walker
+                .interpret_index(&mut base, index)
+                .expect("Interpreter error");
+        }
+        least = least.min(start.elapsed());
+    }
+
+    least
+}
+
+fn main() {
+    for (name, source) in PROGRAMS {
+        println!("{name:<16}{:>10.2?}", time(source));
+    }
+}
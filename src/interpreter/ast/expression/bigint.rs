@@ -0,0 +1,516 @@
+use std::cmp::Ordering;
+
+/// An integer of arbitrary precision.
+///
+/// The magnitude is stored as base 2^32 digits, least significant first and without trailing zeros.
+/// So, each integer has a unique representation, and in particular zero has no digits and is not negative.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    fn new(negative: bool, mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        BigInt {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    /// The number of bytes held by the digits.
+    pub fn size(&self) -> usize {
+        std::mem::size_of_val(self.digits.as_slice())
+    }
+
+    /// The number of bits of the magnitude.
+    pub fn bits(&self) -> u64 {
+        match self.digits.last() {
+            Some(last) => self.digits.len() as u64 * 32 - last.leading_zeros() as u64,
+
+            None => 0,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The integer as an i64, if within the range of an i64.
+    pub fn to_i64(&self) -> Option<i64> {
+        if 2 < self.digits.len() {
+            return None;
+        }
+
+        let magnitude = self
+            .digits
+            .iter()
+            .rev()
+            .fold(0_u64, |m, &d| (m << 32) | d as u64) as i128;
+
+        i64::try_from(if self.negative { -magnitude } else { magnitude }).ok()
+    }
+
+    /// The nearest float to the integer.
+    pub fn to_f64(&self) -> f64 {
+        // Parsing the decimal representation rounds correctly, which summing the digits as floats would not.
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    /// The integral float `n` as an integer, if `n` is integral.
+    pub fn from_f64(n: f64) -> Option<Self> {
+        if !n.is_finite() || n.fract() != 0.0 {
+            return None;
+        }
+
+        let bits = n.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i64;
+        let fraction = bits & ((1 << 52) - 1);
+
+        // Subnormal floats have no implicit leading bit, but are never integral unless zero.
+        let (mantissa, shift) = match exponent {
+            0 => (fraction, 0),
+            _ => (fraction | (1 << 52), exponent - 1075),
+        };
+
+        let magnitude = match shift {
+            0.. => shl_magnitude(&from_u64(mantissa), shift as usize),
+            ..0 => from_u64(mantissa >> -shift),
+        };
+
+        Some(BigInt::new(n < 0.0, magnitude))
+    }
+
+    /// The integer written as `digits` in `radix`, with an optional leading `-`.
+    pub fn from_str_radix(digits: &str, radix: u32) -> Option<Self> {
+        let (negative, digits) = match digits.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, digits),
+        };
+
+        if digits.is_empty() {
+            return None;
+        }
+
+        let mut magnitude = Vec::default();
+        for c in digits.chars() {
+            mul_add_small(&mut magnitude, radix, c.to_digit(radix)?);
+        }
+
+        Some(BigInt::new(negative, magnitude))
+    }
+
+    pub fn neg(&self) -> Self {
+        BigInt::new(!self.negative, self.digits.clone())
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_magnitude(&self.digits, &other.digits));
+        }
+
+        match cmp_magnitude(&self.digits, &other.digits) {
+            Ordering::Less => {
+                BigInt::new(other.negative, sub_magnitude(&other.digits, &self.digits))
+            }
+
+            _ => BigInt::new(self.negative, sub_magnitude(&self.digits, &other.digits)),
+        }
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        BigInt::new(
+            self.negative != other.negative,
+            mul_magnitude(&self.digits, &other.digits),
+        )
+    }
+
+    /// The quotient and remainder of floored division by `other`, if `other` is not zero.
+    ///
+    /// As with integers, the quotient is rounded towards negative infinity and so the remainder has the sign of `other`.
+    pub fn div_rem_floor(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = div_rem_magnitude(&self.digits, &other.digits);
+        let quotient = BigInt::new(self.negative != other.negative, quotient);
+        let remainder = BigInt::new(self.negative, remainder);
+
+        if !remainder.is_zero() && remainder.negative != other.negative {
+            Some((quotient.sub(&BigInt::from(1)), remainder.add(other)))
+        } else {
+            Some((quotient, remainder))
+        }
+    }
+
+    /// The integer with `shift` bits dropped from its magnitude, so rounded towards zero.
+    pub fn shr_magnitude(&self, shift: u64) -> Self {
+        BigInt::new(self.negative, shr_magnitude(&self.digits, shift))
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = BigInt::from(1);
+
+        while 0 < exponent {
+            if exponent & 1 == 1 {
+                result = result.mul(&base);
+            }
+
+            exponent >>= 1;
+            if 0 < exponent {
+                base = base.mul(&base);
+            }
+        }
+
+        result
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        BigInt::new(value < 0, from_u64(value.unsigned_abs()))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+
+            (true, false) => Ordering::Less,
+
+            (false, false) => cmp_magnitude(&self.digits, &other.digits),
+
+            (true, true) => cmp_magnitude(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Chunks of nine decimal digits, least significant first.
+        let mut chunks = Vec::default();
+        let mut magnitude = self.digits.clone();
+        while !magnitude.is_empty() {
+            chunks.push(div_rem_small(&mut magnitude, 1_000_000_000));
+        }
+
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        let mut chunks = chunks.iter().rev();
+        if let Some(most) = chunks.next() {
+            write!(f, "{most}")?;
+        }
+        for chunk in chunks {
+            write!(f, "{chunk:09}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn from_u64(value: u64) -> Vec<u32> {
+    let mut digits = vec![value as u32, (value >> 32) as u32];
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() < b.len() { (b, a) } else { (a, b) };
+
+    let mut sum = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0_u64;
+    for (index, &digit) in long.iter().enumerate() {
+        let total = digit as u64 + *short.get(index).unwrap_or(&0) as u64 + carry;
+        sum.push(total as u32);
+        carry = total >> 32;
+    }
+    if 0 < carry {
+        sum.push(carry as u32);
+    }
+
+    sum
+}
+
+/// The difference `a - b`, where the magnitude of `a` is at least the magnitude of `b`.
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(a.len());
+    let mut borrow = 0_i64;
+    for (index, &digit) in a.iter().enumerate() {
+        let mut total = digit as i64 - *b.get(index).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if total < 0 {
+            total += 1 << 32;
+            borrow = 1;
+        }
+        difference.push(total as u32);
+    }
+
+    difference
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::default();
+    }
+
+    let mut product = vec![0_u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0_u64;
+        for (j, &y) in b.iter().enumerate() {
+            let total = x as u64 * y as u64 + product[i + j] as u64 + carry;
+            product[i + j] = total as u32;
+            carry = total >> 32;
+        }
+        product[i + b.len()] = carry as u32;
+    }
+
+    product
+}
+
+fn mul_add_small(digits: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for digit in digits.iter_mut() {
+        let total = *digit as u64 * factor as u64 + carry;
+        *digit = total as u32;
+        carry = total >> 32;
+    }
+    if 0 < carry {
+        digits.push(carry as u32);
+    }
+}
+
+/// Divide `digits` by `divisor` in place, returning the remainder.
+fn div_rem_small(digits: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder = 0_u64;
+    for digit in digits.iter_mut().rev() {
+        let total = (remainder << 32) | *digit as u64;
+        *digit = (total / divisor as u64) as u32;
+        remainder = total % divisor as u64;
+    }
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    remainder as u32
+}
+
+fn shl_magnitude(digits: &[u32], shift: usize) -> Vec<u32> {
+    let (whole, bits) = (shift / 32, shift % 32);
+
+    let mut shifted = vec![0_u32; whole];
+    let mut carry = 0_u32;
+    for &digit in digits {
+        match bits {
+            0 => shifted.push(digit),
+            _ => {
+                shifted.push((digit << bits) | carry);
+                carry = digit >> (32 - bits);
+            }
+        }
+    }
+    if 0 < carry {
+        shifted.push(carry);
+    }
+
+    shifted
+}
+
+fn shr_magnitude(digits: &[u32], shift: u64) -> Vec<u32> {
+    let whole = usize::try_from(shift / 32).unwrap_or(usize::MAX);
+    let bits = shift % 32;
+
+    let Some(kept) = digits.get(whole..) else {
+        return Vec::default();
+    };
+
+    match bits {
+        0 => kept.to_vec(),
+
+        _ => (0..kept.len())
+            .map(|i| {
+                let high = kept.get(i + 1).map_or(0, |&d| d << (32 - bits));
+                (kept[i] >> bits) | high
+            })
+            .collect(),
+    }
+}
+
+/// The quotient and remainder of `a` divided by `b`, where `b` is not zero.
+///
+/// Division by a single digit is direct, and otherwise proceeds one bit at a time.
+fn div_rem_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if let [divisor] = b {
+        let mut quotient = a.to_vec();
+        let remainder = div_rem_small(&mut quotient, *divisor);
+        return (quotient, from_u64(remainder as u64));
+    }
+
+    let mut quotient = vec![0_u32; a.len()];
+    let mut remainder: Vec<u32> = Vec::default();
+
+    for index in (0..a.len() * 32).rev() {
+        remainder = shl_magnitude(&remainder, 1);
+        if (a[index / 32] >> (index % 32)) & 1 == 1 {
+            match remainder.first_mut() {
+                Some(least) => *least |= 1,
+                None => remainder.push(1),
+            }
+        }
+
+        if cmp_magnitude(&remainder, b) != Ordering::Less {
+            remainder = sub_magnitude(&remainder, b);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[index / 32] |= 1 << (index % 32);
+        }
+    }
+
+    (quotient, remainder)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn big(digits: &str) -> BigInt {
+        BigInt::from_str_radix(digits, 10).unwrap()
+    }
+
+    #[test]
+    fn display_and_parse() {
+        for digits in [
+            "0",
+            "1",
+            "-1",
+            "4294967296",
+            "-18446744073709551616",
+            "1000000000",
+        ] {
+            assert_eq!(big(digits).to_string(), digits);
+        }
+
+        assert_eq!(
+            BigInt::from_str_radix("ffffffffffffffffff", 16)
+                .unwrap()
+                .to_string(),
+            "4722366482869645213695"
+        );
+
+        assert_eq!(BigInt::from_str_radix("-0", 10), Some(BigInt::default()));
+        assert_eq!(BigInt::from_str_radix("12a", 10), None);
+    }
+
+    #[test]
+    fn i64_round_trip() {
+        for i in [0, 1, -1, i64::MAX, i64::MIN, 1 << 32] {
+            assert_eq!(BigInt::from(i).to_i64(), Some(i));
+        }
+
+        assert_eq!(BigInt::from(i64::MAX).add(&BigInt::from(1)).to_i64(), None);
+        assert_eq!(BigInt::from(i64::MIN).sub(&BigInt::from(1)).to_i64(), None);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = big("123456789012345678901234567890");
+        let b = big("-987654321098765432109876543210");
+
+        assert_eq!(a.add(&b), big("-864197532086419753208641975320"));
+        assert_eq!(a.sub(&b), big("1111111110111111111011111111100"));
+        assert_eq!(
+            a.mul(&b),
+            big("-121932631137021795226185032733622923332237463801111263526900")
+        );
+        assert_eq!(big("2").pow(100), big("1267650600228229401496703205376"));
+    }
+
+    #[test]
+    fn bits() {
+        assert_eq!(big("0").bits(), 0);
+        assert_eq!(big("-1").bits(), 1);
+        assert_eq!(big("2").pow(100).bits(), 101);
+    }
+
+    #[test]
+    fn shifts() {
+        assert_eq!(big("2").pow(100).shr_magnitude(98), big("4"));
+        assert_eq!(
+            big("-1267650600228229401496703205377").shr_magnitude(33),
+            big("-147573952589676412928")
+        );
+        assert_eq!(big("12345").shr_magnitude(64), big("0"));
+    }
+
+    #[test]
+    fn floored_division() {
+        let (q, r) = big("-7").div_rem_floor(&big("2")).unwrap();
+        assert_eq!((q, r), (big("-4"), big("1")));
+
+        let (q, r) = big("7").div_rem_floor(&big("-2")).unwrap();
+        assert_eq!((q, r), (big("-4"), big("-1")));
+
+        let a = big("1267650600228229401496703205377");
+        let b = big("18446744073709551617");
+        let (q, r) = a.div_rem_floor(&b).unwrap();
+        assert_eq!(q.mul(&b).add(&r), a);
+        assert!(r < b);
+
+        assert_eq!(a.div_rem_floor(&BigInt::default()), None);
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(
+            BigInt::from_f64(2.0_f64.powi(70)),
+            Some(big("1180591620717411303424"))
+        );
+        assert_eq!(BigInt::from_f64(-3.0), Some(big("-3")));
+        assert_eq!(BigInt::from_f64(0.5), None);
+        assert_eq!(BigInt::from_f64(f64::INFINITY), None);
+
+        assert_eq!(big("1180591620717411303424").to_f64(), 2.0_f64.powi(70));
+    }
+
+    #[test]
+    fn ordering() {
+        assert!(big("-18446744073709551616") < big("-1"));
+        assert!(big("-1") < big("0"));
+        assert!(big("18446744073709551616") > big("4294967296"));
+    }
+}
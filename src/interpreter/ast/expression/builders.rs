@@ -1,6 +1,11 @@
-use crate::interpreter::ast::{
-    expression::{Expr, OpOne, OpTwo},
-    identifier::Identifier,
+use std::rc::Rc;
+
+use crate::interpreter::{
+    ast::{
+        expression::{Expr, OpOne, OpTwo},
+        identifier::{Address, Id, Identifier},
+    },
+    location::Location,
 };
 
 use super::ExprB;
@@ -13,6 +18,15 @@ impl Expr {
         }
     }
 
+    pub fn mk_update(op: OpTwo, target: Expr, e: Expr, postfix: bool) -> Self {
+        Expr::Update {
+            op,
+            target: Box::new(target),
+            e: Box::new(e),
+            postfix,
+        }
+    }
+
     pub fn mk_binary(op: OpTwo, a: Expr, b: Expr) -> Self {
         Expr::Binary {
             op,
@@ -25,17 +39,29 @@ impl Expr {
         Expr::Unary { op, e: Box::new(a) }
     }
 
+    pub fn mk_integer(i: i64) -> Self {
+        Expr::Basic(ExprB::Integer { i })
+    }
+
     pub fn mk_numeric(n: f64) -> Self {
         Expr::Basic(ExprB::Numeric { n })
     }
 
-    pub fn mk_string(s: String) -> Self {
-        Expr::Basic(ExprB::String { s })
+    pub fn mk_string(s: impl Into<Rc<str>>) -> Self {
+        Expr::Basic(ExprB::mk_string(s))
     }
 
-    pub fn mk_identifier(id: String, offset: Option<usize>) -> Self {
+    pub fn mk_identifier(id: Id, address: Option<Address>) -> Self {
         Expr::Identifier {
-            id: Identifier::fresh(id, offset),
+            id: Identifier::fresh(id, address),
+        }
+    }
+
+    pub fn mk_ternary(condition: Expr, case_if: Expr, case_else: Expr) -> Self {
+        Expr::Ternary {
+            condition: Box::new(condition),
+            case_if: Box::new(case_if),
+            case_else: Box::new(case_else),
         }
     }
 
@@ -65,12 +91,60 @@ impl Expr {
         Expr::Basic(ExprB::Nil)
     }
 
-    pub fn mk_call(caller: Expr, args: Vec<Expr>) -> Self {
+    pub fn mk_call(caller: Expr, args: Vec<Expr>, location: Location) -> Self {
         Expr::Call {
             caller: Box::new(caller),
             args,
+            location,
         }
     }
+
+    pub fn mk_list(elements: Vec<Expr>) -> Self {
+        Expr::List { elements }
+    }
+
+    pub fn mk_map(entries: Vec<(Expr, Expr)>) -> Self {
+        Expr::Map { entries }
+    }
+
+    pub fn mk_range(start: Expr, end: Expr, inclusive: bool) -> Self {
+        Expr::Range {
+            start: Box::new(start),
+            end: Box::new(end),
+            inclusive,
+        }
+    }
+
+    pub fn mk_get(target: Expr, name: Id) -> Self {
+        Expr::Get {
+            target: Box::new(target),
+            name,
+        }
+    }
+
+    pub fn mk_interpolation(parts: Vec<Expr>) -> Self {
+        Expr::Interpolation { parts }
+    }
+
+    pub fn mk_located(location: Location, e: Expr) -> Self {
+        Expr::Located {
+            location,
+            e: Box::new(e),
+        }
+    }
+
+    pub fn mk_index(target: Expr, index: Expr) -> Self {
+        Expr::Index {
+            target: Box::new(target),
+            index: Box::new(index),
+        }
+    }
+}
+
+impl From<i64> for Expr {
+    fn from(value: i64) -> Self {
+        Expr::Basic(ExprB::Integer { i: value })
+    }
 }
 
 impl From<f64> for Expr {
@@ -81,8 +155,6 @@ impl From<f64> for Expr {
 
 impl From<&str> for Expr {
     fn from(value: &str) -> Self {
-        Expr::Basic(ExprB::String {
-            s: value.to_owned(),
-        })
+        Expr::Basic(ExprB::String { s: value.into() })
     }
 }
@@ -0,0 +1,71 @@
+use std::{
+    ops::{Deref, DerefMut},
+    rc::Rc,
+};
+
+use super::{ExprB, Formatting};
+
+/// The elements of a list.
+///
+/// A list is dropped without recursing into the lists and maps it holds, so dropping a deeply nested list does not overflow the stack.
+#[derive(Clone, Default)]
+pub struct List {
+    elements: Vec<ExprB>,
+}
+
+impl From<Vec<ExprB>> for List {
+    fn from(elements: Vec<ExprB>) -> Self {
+        List { elements }
+    }
+}
+
+impl Deref for List {
+    type Target = Vec<ExprB>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.elements
+    }
+}
+
+impl DerefMut for List {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.elements
+    }
+}
+
+impl Drop for List {
+    fn drop(&mut self) {
+        drop_values(std::mem::take(&mut self.elements));
+    }
+}
+
+impl std::fmt::Debug for List {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(_formatting) = Formatting::enter(self as *const List as usize) else {
+            return write!(f, "[...]");
+        };
+
+        f.debug_list().entries(self.elements.iter()).finish()
+    }
+}
+
+/// Drop `values`, taking the values of each list and map held only by `values` to be dropped in turn, rather than recursing.
+pub(super) fn drop_values(mut values: Vec<ExprB>) {
+    while let Some(value) = values.pop() {
+        match value {
+            ExprB::List { l } => {
+                if let Ok(list) = Rc::try_unwrap(l) {
+                    values.append(&mut list.into_inner().elements);
+                }
+            }
+
+            ExprB::Map { m } => {
+                if let Ok(map) = Rc::try_unwrap(m) {
+                    values.append(&mut map.into_inner().take_values());
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
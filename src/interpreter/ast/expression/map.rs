@@ -0,0 +1,214 @@
+use std::{collections::HashMap, rc::Rc};
+
+use super::{BigInt, ExprB, Formatting, list::drop_values, number::integral_float};
+
+/// A key of a map.
+///
+/// Keys are restricted to values which hash consistently with their equality, and so to strings, numbers, booleans, and nil.
+/// As `NaN` is not equal to itself it is not a valid key, and `-0` is stored as `0`.
+/// As integral floats are equal to integers, such floats are stored as integers, so `1.0` and `1` are the same key.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    BigInteger { i: BigInt },
+
+    Boolean { b: bool },
+
+    Integer { i: i64 },
+
+    Nil,
+
+    Numeric { bits: u64 },
+
+    String { s: Rc<str> },
+}
+
+impl MapKey {
+    pub fn from_expr(expr: &ExprB) -> Option<MapKey> {
+        match expr {
+            ExprB::BigInteger { i } => Some(MapKey::BigInteger { i: i.clone() }),
+
+            ExprB::Boolean { b } => Some(MapKey::Boolean { b: *b }),
+
+            ExprB::Integer { i } => Some(MapKey::Integer { i: *i }),
+
+            ExprB::Nil => Some(MapKey::Nil),
+
+            ExprB::Numeric { n } if n.is_nan() => None,
+
+            ExprB::Numeric { n } if let Some(i) = integral_float(*n) => {
+                MapKey::from_expr(&i.to_expr())
+            }
+
+            ExprB::Numeric { n } => Some(MapKey::Numeric {
+                bits: (n + 0.0).to_bits(),
+            }),
+
+            ExprB::String { s } => Some(MapKey::String { s: s.clone() }),
+
+            _ => None,
+        }
+    }
+
+    pub fn to_expr(&self) -> ExprB {
+        match self {
+            MapKey::BigInteger { i } => ExprB::BigInteger { i: i.clone() },
+
+            MapKey::Boolean { b } => ExprB::mk_bool(*b),
+
+            MapKey::Integer { i } => ExprB::mk_integer(*i),
+
+            MapKey::Nil => ExprB::Nil,
+
+            MapKey::Numeric { bits } => ExprB::mk_numeric(f64::from_bits(*bits)),
+
+            MapKey::String { s } => ExprB::mk_string(s.clone()),
+        }
+    }
+}
+
+/// A map from keys to values which iterates in insertion order.
+///
+/// Reassigning a key keeps the position of the key, while deleting and then reinserting a key moves the key to the end.
+/// As with a list, a map is dropped without recursing into the lists and maps it holds.
+#[derive(Clone, Default)]
+pub struct Map {
+    entries: Vec<(MapKey, ExprB)>,
+    positions: HashMap<MapKey, usize>,
+}
+
+impl Map {
+    /// The number of bytes held for each entry, as an entry and a position, without the bytes held by the key or value.
+    pub const ENTRY_SIZE: usize =
+        std::mem::size_of::<(MapKey, ExprB)>() + std::mem::size_of::<(MapKey, usize)>();
+
+    pub fn get(&self, key: &MapKey) -> Option<&ExprB> {
+        self.positions.get(key).map(|&p| &self.entries[p].1)
+    }
+
+    pub fn contains_key(&self, key: &MapKey) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    pub fn insert(&mut self, key: MapKey, value: ExprB) -> Option<ExprB> {
+        match self.positions.get(&key) {
+            Some(&p) => Some(std::mem::replace(&mut self.entries[p].1, value)),
+
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &MapKey) -> Option<ExprB> {
+        let position = self.positions.remove(key)?;
+        let (_, value) = self.entries.remove(position);
+
+        for (key, _) in &self.entries[position..] {
+            if let Some(p) = self.positions.get_mut(key) {
+                *p -= 1;
+            }
+        }
+
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.positions.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&MapKey, &ExprB)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &MapKey> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &ExprB> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    /// The values of the map, leaving the map empty.
+    pub fn take_values(&mut self) -> Vec<ExprB> {
+        self.positions.clear();
+        std::mem::take(&mut self.entries)
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect()
+    }
+}
+
+impl Drop for Map {
+    fn drop(&mut self) {
+        drop_values(self.take_values());
+    }
+}
+
+impl std::fmt::Debug for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(_formatting) = Formatting::enter(self as *const Map as usize) else {
+            return write!(f, "{{...}}");
+        };
+
+        f.debug_struct("Map")
+            .field("entries", &self.entries)
+            .field("positions", &self.positions)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(s: &str) -> MapKey {
+        MapKey::String { s: s.into() }
+    }
+
+    #[test]
+    fn insertion_order() {
+        let mut map = Map::default();
+
+        map.insert(key("b"), ExprB::mk_numeric(1.0));
+        map.insert(key("a"), ExprB::mk_numeric(2.0));
+        map.insert(key("c"), ExprB::mk_numeric(3.0));
+        map.insert(key("b"), ExprB::mk_numeric(4.0));
+
+        let keys = map.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(keys, vec![key("b"), key("a"), key("c")]);
+        assert_eq!(map.get(&key("b")), Some(&ExprB::mk_numeric(4.0)));
+
+        assert_eq!(map.remove(&key("b")), Some(ExprB::mk_numeric(4.0)));
+        map.insert(key("b"), ExprB::mk_numeric(5.0));
+
+        let keys = map.keys().cloned().collect::<Vec<_>>();
+        assert_eq!(keys, vec![key("a"), key("c"), key("b")]);
+        assert_eq!(map.get(&key("c")), Some(&ExprB::mk_numeric(3.0)));
+    }
+
+    #[test]
+    fn numeric_keys() {
+        assert_eq!(
+            MapKey::from_expr(&ExprB::mk_numeric(-0.0)),
+            MapKey::from_expr(&ExprB::mk_numeric(0.0))
+        );
+
+        assert_eq!(MapKey::from_expr(&ExprB::mk_numeric(f64::NAN)), None);
+
+        assert_eq!(
+            MapKey::from_expr(&ExprB::mk_numeric(1.0)),
+            MapKey::from_expr(&ExprB::mk_integer(1))
+        );
+    }
+}
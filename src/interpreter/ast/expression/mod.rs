@@ -1,22 +1,59 @@
+pub mod bigint;
 mod builders;
+pub mod list;
+pub mod map;
+pub mod number;
 mod operators;
+pub mod value;
 
+use std::{cell::RefCell, rc::Rc};
+
+pub use bigint::BigInt;
+pub use list::List;
+pub use map::{Map, MapKey};
+pub use number::Number;
 pub use operators::{OpOne, OpTwo};
+pub use value::Value;
+
+use crate::interpreter::{environment::EnvHandle, location::Location, natives::Native};
+
+use super::{
+    identifier::{Id, Identifier},
+    statement::Statements,
+};
 
-use crate::interpreter::environment::EnvHandle;
+pub type ListHandle = Rc<RefCell<List>>;
 
-use super::{identifier::Identifier, statement::Statements};
+pub type MapHandle = Rc<RefCell<Map>>;
 
 #[derive(Clone, Debug)]
 pub enum ExprB {
+    BigInteger {
+        i: BigInt,
+    },
+
     Boolean {
         b: bool,
     },
 
+    Integer {
+        i: i64,
+    },
+
     Lambda {
-        env: EnvHandle,
-        params: Vec<Identifier>,
-        body: Statements,
+        closure: Rc<Closure>,
+    },
+
+    List {
+        l: ListHandle,
+    },
+
+    Map {
+        m: MapHandle,
+    },
+
+    Native {
+        native: Native,
     },
 
     Nil,
@@ -25,26 +62,77 @@ pub enum ExprB {
         n: f64,
     },
 
+    Range {
+        start: i64,
+        end: i64,
+        inclusive: bool,
+    },
+
     String {
-        s: String,
+        s: Rc<str>,
     },
 }
 
+/// A function declared in the source, with the environment the function closes over.
+///
+/// Each evaluation of a declaration is a distinct closure, though all closures of a declaration share the parameters and body of the declaration.
+#[derive(Debug)]
+pub struct Closure {
+    pub name: Rc<str>,
+    pub env: EnvHandle,
+    pub params: Rc<[Identifier]>,
+    pub body: Rc<Statements>,
+}
+
+/// Equality of values.
+///
+/// - Nil is equal to nil, and only to nil.
+/// - Booleans and strings are equal when their contents are equal.
+/// - Numbers are equal when they denote the same number, so `1 == 1.0`, and as `NaN` denotes no number `NaN` is equal to nothing, itself included.
+/// - Lists, maps and functions are equal only to themselves, and not to a distinct value with the same contents.
+/// - A native function is equal to a native function of the same name bound to an equal value, if any.
+/// - Ranges are equal when their bounds and inclusivity are equal.
+///
+/// Values of different types are never equal, with the exception of integers and floats.
 impl PartialEq for ExprB {
     fn eq(&self, other: &Self) -> bool {
         use ExprB::*;
 
         match (self, other) {
-            (Nil, _) => false,
-
-            (_, Nil) => false,
+            (Nil, Nil) => true,
 
             (Boolean { b: l }, Boolean { b: r }) => l == r,
 
-            (Numeric { n: l }, Numeric { n: r }) => l == r,
+            (
+                BigInteger { .. } | Integer { .. } | Numeric { .. },
+                BigInteger { .. } | Integer { .. } | Numeric { .. },
+            ) => self.number() == other.number(),
 
             (String { s: l }, String { s: r }) => l == r,
 
+            (List { l }, List { l: r }) => Rc::ptr_eq(l, r),
+
+            (Map { m: l }, Map { m: r }) => Rc::ptr_eq(l, r),
+
+            (Lambda { closure: l }, Lambda { closure: r }) => Rc::ptr_eq(l, r),
+
+            (Native { native: l }, Native { native: r }) => {
+                l.name == r.name && l.receiver == r.receiver
+            }
+
+            (
+                Range {
+                    start: ls,
+                    end: le,
+                    inclusive: li,
+                },
+                Range {
+                    start: rs,
+                    end: re,
+                    inclusive: ri,
+                },
+            ) => ls == rs && le == re && li == ri,
+
             _ => false,
         }
     }
@@ -57,15 +145,111 @@ impl std::fmt::Display for ExprB {
 
             Self::Boolean { b } => write!(f, "{b}"),
 
-            Self::Numeric { n } => write!(f, "{n}"),
+            Self::BigInteger { i } => write!(f, "{i}"),
+
+            Self::Integer { i } => write!(f, "{i}"),
+
+            // Debug formatting keeps the fractional part of integral floats, so `2.0` is distinct from `2`.
+            Self::Numeric { n } => write!(f, "{n:?}"),
 
             Self::String { s } => write!(f, "{s}"),
 
-            Self::Lambda {
-                env: _,
-                params: _,
-                body: _,
-            } => write!(f, "λ"),
+            Self::Lambda { .. } => write!(f, "λ"),
+
+            Self::List { l } => {
+                let Some(_formatting) = Formatting::enter(l.as_ptr() as usize) else {
+                    return write!(f, "[...]");
+                };
+
+                write!(f, "[")?;
+                for (index, element) in l.borrow().iter().enumerate() {
+                    if 0 < index {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element.repr())?;
+                }
+                write!(f, "]")
+            }
+
+            Self::Map { m } => {
+                let Some(_formatting) = Formatting::enter(m.as_ptr() as usize) else {
+                    return write!(f, "{{...}}");
+                };
+
+                write!(f, "{{")?;
+                for (index, (key, value)) in m.borrow().iter().enumerate() {
+                    if 0 < index {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key.to_expr().repr(), value.repr())?;
+                }
+                write!(f, "}}")
+            }
+
+            Self::Native { native } => write!(f, "<native {}>", native.name),
+
+            Self::Range {
+                start,
+                end,
+                inclusive,
+            } => match inclusive {
+                true => write!(f, "{start}..={end}"),
+                false => write!(f, "{start}..{end}"),
+            },
+        }
+    }
+}
+
+thread_local! {
+    /// The addresses of the lists and maps being formatted, innermost last.
+    static FORMATTING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Marks a list or map as being formatted, until dropped.
+///
+/// A list or map which holds itself is formatted as `[...]` or `{...}` where it repeats, rather than without end.
+struct Formatting(usize);
+
+impl Formatting {
+    /// A guard for the list or map at `address`, or none if the list or map is already being formatted.
+    fn enter(address: usize) -> Option<Formatting> {
+        FORMATTING.with_borrow_mut(|formatting| match formatting.contains(&address) {
+            true => None,
+
+            false => {
+                formatting.push(address);
+                Some(Formatting(address))
+            }
+        })
+    }
+}
+
+impl Drop for Formatting {
+    fn drop(&mut self) {
+        FORMATTING.with_borrow_mut(|formatting| formatting.retain(|&address| address != self.0));
+    }
+}
+
+/// Displays an expression as it would be written in source, used for the elements of lists and maps.
+///
+/// Strings are quoted and escaped, and all other expressions display as usual.
+pub struct Repr<'e>(&'e ExprB);
+
+impl std::fmt::Display for Repr<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            ExprB::String { s } => {
+                let escaped = s
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n")
+                    .replace('\t', "\\t")
+                    .replace("${", "\\${");
+
+                write!(f, "\"{escaped}\"")
+            }
+
+            expr => write!(f, "{expr}"),
         }
     }
 }
@@ -73,12 +257,24 @@ impl std::fmt::Display for ExprB {
 impl ExprB {
     pub fn is_truthy(&self) -> bool {
         match self {
+            Self::BigInteger { .. } => true,
+
+            Self::Integer { .. } => true,
+
             Self::Numeric { .. } => true,
 
             Self::String { .. } => true,
 
             Self::Lambda { .. } => true,
 
+            Self::List { .. } => true,
+
+            Self::Map { .. } => true,
+
+            Self::Native { .. } => true,
+
+            Self::Range { .. } => true,
+
             Self::Boolean { b } => *b,
 
             Self::Nil => false,
@@ -88,6 +284,78 @@ impl ExprB {
     pub fn is_falsey(&self) -> bool {
         !self.is_truthy()
     }
+
+    pub fn repr(&self) -> Repr<'_> {
+        Repr(self)
+    }
+
+    /// The name of the type of the expression, as used in type errors.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::BigInteger { .. } | Self::Integer { .. } => "integer",
+
+            Self::Boolean { .. } => "boolean",
+
+            Self::Lambda { .. } | Self::Native { .. } => "function",
+
+            Self::List { .. } => "list",
+
+            Self::Map { .. } => "map",
+
+            Self::Nil => "nil",
+
+            Self::Numeric { .. } => "float",
+
+            Self::Range { .. } => "range",
+
+            Self::String { .. } => "string",
+        }
+    }
+
+    /// Whether two values are the same value.
+    ///
+    /// Lists, maps and functions are the same when equal, as equality of these is identity.
+    /// Otherwise, values are the same when of the same type and equal, with the exception of floats, which are the same when bitwise identical.
+    /// So, `1` and `1.0` are equal but not the same, while `NaN` is the same as `NaN` though not equal to `NaN`.
+    pub fn same(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Numeric { n: l }, Self::Numeric { n: r }) => l.to_bits() == r.to_bits(),
+
+            (l, r) => l.type_name() == r.type_name() && l == r,
+        }
+    }
+
+    /// The number of bytes held by the value beyond the value itself.
+    ///
+    /// The elements of a list or map are counted as values, without the bytes held by each element.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::BigInteger { i } => i.size(),
+
+            Self::Lambda { .. } => std::mem::size_of::<Closure>(),
+
+            Self::List { l } => l.borrow().len() * std::mem::size_of::<ExprB>(),
+
+            Self::Map { m } => m.borrow().len() * Map::ENTRY_SIZE,
+
+            Self::String { s } => s.len(),
+
+            _ => 0,
+        }
+    }
+
+    /// The expression as a number, if the expression is an integer or a float.
+    pub fn number(&self) -> Option<Number> {
+        match self {
+            Self::BigInteger { i } => Some(Number::BigInteger(i.clone())),
+
+            Self::Integer { i } => Some(Number::Integer(*i)),
+
+            Self::Numeric { n } => Some(Number::Float(*n)),
+
+            _ => None,
+        }
+    }
 }
 
 impl ExprB {
@@ -95,12 +363,44 @@ impl ExprB {
         Self::Boolean { b }
     }
 
+    pub fn mk_integer(i: i64) -> ExprB {
+        Self::Integer { i }
+    }
+
     pub fn mk_numeric(n: f64) -> ExprB {
         Self::Numeric { n }
     }
 
-    pub fn mk_string(s: String) -> ExprB {
-        Self::String { s }
+    pub fn mk_string(s: impl Into<Rc<str>>) -> ExprB {
+        Self::String { s: s.into() }
+    }
+
+    pub fn mk_lambda(
+        name: Rc<str>,
+        env: EnvHandle,
+        params: Rc<[Identifier]>,
+        body: Rc<Statements>,
+    ) -> ExprB {
+        Self::Lambda {
+            closure: Rc::new(Closure {
+                name,
+                env,
+                params,
+                body,
+            }),
+        }
+    }
+
+    pub fn mk_list(elements: Vec<ExprB>) -> ExprB {
+        Self::List {
+            l: Rc::new(RefCell::new(List::from(elements))),
+        }
+    }
+
+    pub fn mk_map(map: Map) -> ExprB {
+        Self::Map {
+            m: Rc::new(RefCell::new(map)),
+        }
     }
 }
 
@@ -119,6 +419,16 @@ pub enum Expr {
         e: Box<Expr>,
     },
 
+    /// An update of `target` to the result of `op` applied to `target` and `e`, evaluating `target` once.
+    ///
+    /// The value of the update is the updated value, unless `postfix`, in which case the value is that prior to the update.
+    Update {
+        op: OpTwo,
+        target: Box<Expr>,
+        e: Box<Expr>,
+        postfix: bool,
+    },
+
     Unary {
         op: OpOne,
         e: Box<Expr>,
@@ -130,6 +440,12 @@ pub enum Expr {
         b: Box<Expr>,
     },
 
+    Ternary {
+        condition: Box<Expr>,
+        case_if: Box<Expr>,
+        case_else: Box<Expr>,
+    },
+
     Or {
         a: Box<Expr>,
         b: Box<Expr>,
@@ -144,9 +460,47 @@ pub enum Expr {
         e: Box<Expr>,
     },
 
+    /// A call of `caller` with `args`, at `location`.
     Call {
         caller: Box<Expr>,
         args: Vec<Expr>,
+        location: Location,
+    },
+
+    List {
+        elements: Vec<Expr>,
+    },
+
+    Map {
+        entries: Vec<(Expr, Expr)>,
+    },
+
+    Index {
+        target: Box<Expr>,
+        index: Box<Expr>,
+    },
+
+    /// The property `name` of `target`.
+    Get {
+        target: Box<Expr>,
+        name: Id,
+    },
+
+    Range {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        inclusive: bool,
+    },
+
+    /// A string formed by concatenating the display of each part.
+    Interpolation {
+        parts: Vec<Expr>,
+    },
+
+    /// An expression together with the location of its source, used to locate runtime errors within the expression.
+    Located {
+        location: Location,
+        e: Box<Expr>,
     },
 }
 
@@ -164,17 +518,33 @@ impl std::fmt::Display for Expr {
                 e: assignment,
             } => write!(f, "{name} = {assignment}"),
 
+            Self::Update {
+                op,
+                target,
+                e,
+                postfix,
+            } => match postfix {
+                true => write!(f, "(post{op}= {target} {e})"),
+                false => write!(f, "({op}= {target} {e})"),
+            },
+
             Self::Grouping { e } => write!(f, "(group {e})"),
 
             Self::Unary { op, e } => write!(f, "({op} {e})"),
 
             Self::Binary { op, a, b } => write!(f, "({op} {a} {b})"),
 
+            Self::Ternary {
+                condition,
+                case_if,
+                case_else,
+            } => write!(f, "(? {condition} {case_if} {case_else})"),
+
             Self::Or { a, b } => write!(f, "(OR {a} {b})"),
 
             Self::And { a, b } => write!(f, "(AND {a} {b})"),
 
-            Self::Call { caller, args } => write!(
+            Self::Call { caller, args, .. } => write!(
                 f,
                 "{}({})",
                 caller,
@@ -183,6 +553,51 @@ impl std::fmt::Display for Expr {
                     .collect::<Vec<_>>()
                     .join(", ")
             ),
+
+            Self::List { elements } => write!(
+                f,
+                "[{}]",
+                elements
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+
+            Self::Map { entries } => write!(
+                f,
+                "{{{}}}",
+                entries
+                    .iter()
+                    .map(|(k, v)| format!("{k}: {v}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+
+            Self::Index { target, index } => write!(f, "{target}[{index}]"),
+
+            Self::Get { target, name } => write!(f, "{target}.{name}"),
+
+            Self::Interpolation { parts } => write!(
+                f,
+                "(interpolate {})",
+                parts
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+
+            Self::Located { location: _, e } => write!(f, "{e}"),
+
+            Self::Range {
+                start,
+                end,
+                inclusive,
+            } => match inclusive {
+                true => write!(f, "(..= {start} {end})"),
+                false => write!(f, "(.. {start} {end})"),
+            },
         }
     }
 }
@@ -191,6 +606,10 @@ impl std::fmt::Display for Expr {
 mod test {
     use super::*;
 
+    use crate::interpreter::{
+        Base, TreeWalker, environment::Env, err::StumbleKind, interner::Interner,
+    };
+
     #[test]
     fn simple_display() {
         let ast = Expr::Binary {
@@ -198,7 +617,7 @@ mod test {
 
             a: Box::new(Expr::Unary {
                 op: OpOne::Minus,
-                e: Box::new(Expr::Basic(ExprB::Numeric { n: 123_f64 })),
+                e: Box::new(Expr::Basic(ExprB::Integer { i: 123 })),
             }),
 
             b: Box::new(Expr::Grouping {
@@ -208,4 +627,142 @@ mod test {
 
         assert_eq!(format!("{ast}"), "(* (- 123) (group 45.67))");
     }
+
+    /// One value of each variant, no two of which are equal.
+    fn samples() -> Vec<ExprB> {
+        fn nil_native(_: &TreeWalker, _: &mut Base, _: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+            Ok(ExprB::Nil)
+        }
+
+        let mut interner = Interner::default();
+
+        vec![
+            ExprB::BigInteger {
+                i: BigInt::from(i64::MAX).mul(&BigInt::from(4)),
+            },
+            ExprB::mk_bool(true),
+            ExprB::mk_integer(1),
+            ExprB::mk_lambda(
+                "f".into(),
+                Env::fresh_std_env(&mut interner),
+                Rc::default(),
+                Rc::default(),
+            ),
+            ExprB::mk_list(vec![ExprB::mk_integer(1)]),
+            ExprB::mk_map(Map::default()),
+            ExprB::Native {
+                native: Native::new("nil", 0, nil_native),
+            },
+            ExprB::Nil,
+            ExprB::mk_numeric(1.5),
+            ExprB::Range {
+                start: 0,
+                end: 1,
+                inclusive: false,
+            },
+            ExprB::mk_string("1".to_owned()),
+        ]
+    }
+
+    #[test]
+    fn equality_of_variants() {
+        let samples = samples();
+
+        for (i, l) in samples.iter().enumerate() {
+            for (j, r) in samples.iter().enumerate() {
+                assert_eq!(l == r, i == j, "{l:?} == {r:?}");
+                assert_eq!(l == &r.clone(), i == j, "{l:?} == {r:?}");
+                assert_eq!(l.same(r), i == j, "same({l:?}, {r:?})");
+            }
+        }
+    }
+
+    #[test]
+    fn compact_values() {
+        let samples = samples();
+
+        for (l, r) in samples
+            .iter()
+            .flat_map(|l| samples.iter().map(move |r| (l, r)))
+        {
+            assert_eq!(
+                Value::from(l.clone()) == Value::from(r.clone()),
+                l == r,
+                "{l:?} == {r:?}"
+            );
+        }
+
+        for sample in samples {
+            let value = Value::from(sample.clone());
+
+            assert_eq!(value.type_name(), sample.type_name());
+            assert_eq!(value.is_truthy(), sample.is_truthy());
+            assert_eq!(value.to_string(), sample.to_string());
+
+            assert!(ExprB::from(&value).same(&sample), "{sample:?}");
+            assert_eq!(value, Value::from(sample));
+        }
+    }
+
+    #[test]
+    fn replaced_values() {
+        let samples = samples();
+
+        for (l, r) in samples
+            .iter()
+            .flat_map(|l| samples.iter().map(move |r| (l, r)))
+        {
+            let mut value = Value::from(l.clone());
+
+            assert!(value.replace(r.clone()).same(l), "{l:?} for {r:?}");
+            assert!(ExprB::from(&value).same(r), "{r:?} for {l:?}");
+        }
+
+        let mut value = Value::from(ExprB::mk_string("a"));
+        let Value::String(held) = &value else {
+            panic!("Expected a string");
+        };
+        let held = held.as_ref() as *const Rc<str>;
+
+        value.replace(ExprB::mk_string("b"));
+
+        assert!(matches!(&value, Value::String(s) if std::ptr::eq(s.as_ref(), held)));
+    }
+
+    #[test]
+    fn equality_of_numbers() {
+        let nan = ExprB::mk_numeric(f64::NAN);
+
+        assert_eq!(ExprB::mk_integer(1), ExprB::mk_numeric(1.0));
+        assert!(!ExprB::mk_integer(1).same(&ExprB::mk_numeric(1.0)));
+
+        assert_ne!(nan, nan);
+        assert!(nan.same(&nan));
+
+        assert_eq!(ExprB::mk_numeric(0.0), ExprB::mk_numeric(-0.0));
+        assert!(!ExprB::mk_numeric(0.0).same(&ExprB::mk_numeric(-0.0)));
+    }
+
+    #[test]
+    fn equality_of_references() {
+        let list = ExprB::mk_list(Vec::default());
+        assert_eq!(list, list.clone());
+        assert_ne!(list, ExprB::mk_list(Vec::default()));
+
+        let body = Rc::new(Statements::default());
+        let lambda = || {
+            let mut interner = Interner::default();
+
+            ExprB::mk_lambda(
+                "f".into(),
+                Env::fresh_std_env(&mut interner),
+                Rc::default(),
+                body.clone(),
+            )
+        };
+
+        let f = lambda();
+        assert_eq!(f, f.clone());
+        assert_ne!(f, lambda());
+    }
 }
@@ -0,0 +1,202 @@
+use std::cmp::Ordering;
+
+use super::{ExprB, bigint::BigInt};
+
+/// 2^63, the least float greater than every integer.
+const INTEGER_BOUND: f64 = 9_223_372_036_854_775_808.0;
+
+/// A number, either an integer or a float.
+///
+/// Integers are held as an i64 when within range, and otherwise as a big integer.
+///
+/// Integers and floats are compared by the numbers they denote, and so `1 == 1.0` while `1 < 1.5`.
+/// Comparisons are exact, so an integer beyond 2^53 is only equal to a float which represents the integer exactly.
+#[derive(Clone, Debug)]
+pub enum Number {
+    Integer(i64),
+
+    BigInteger(BigInt),
+
+    Float(f64),
+}
+
+impl Number {
+    /// The nearest float to the number.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::Integer(i) => *i as f64,
+
+            Number::BigInteger(b) => b.to_f64(),
+
+            Number::Float(n) => *n,
+        }
+    }
+
+    /// The number as a big integer, if the number is an integer.
+    pub fn to_big(&self) -> Option<BigInt> {
+        match self {
+            Number::Integer(i) => Some(BigInt::from(*i)),
+
+            Number::BigInteger(b) => Some(b.clone()),
+
+            Number::Float(_) => None,
+        }
+    }
+
+    pub fn to_expr(self) -> ExprB {
+        match self {
+            Number::Integer(i) => ExprB::mk_integer(i),
+
+            Number::BigInteger(b) => ExprB::BigInteger { i: b },
+
+            Number::Float(n) => ExprB::mk_numeric(n),
+        }
+    }
+
+    /// The ordering of two numbers, if any, as `NaN` is unordered.
+    pub fn compare(&self, other: &Number) -> Option<Ordering> {
+        use Number::*;
+
+        match (self, other) {
+            (Integer(l), Integer(r)) => Some(l.cmp(r)),
+
+            (Float(l), Float(r)) => l.partial_cmp(r),
+
+            (Integer(i), Float(n)) => compare_integer_float(*i, *n),
+
+            (BigInteger(b), Float(n)) => compare_big_float(b, *n),
+
+            (Float(_), _) => other.compare(self).map(Ordering::reverse),
+
+            (l, r) => Some(l.to_big()?.cmp(&r.to_big()?)),
+        }
+    }
+}
+
+impl From<BigInt> for Number {
+    /// The integer as an i64 if within range, and otherwise as a big integer.
+    fn from(value: BigInt) -> Self {
+        match value.to_i64() {
+            Some(i) => Number::Integer(i),
+
+            None => Number::BigInteger(value),
+        }
+    }
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.compare(other) == Some(Ordering::Equal)
+    }
+}
+
+/// `n` as an integer, if `n` is integral.
+pub fn integral_float(n: f64) -> Option<Number> {
+    if n.fract() == 0.0 && (-INTEGER_BOUND..INTEGER_BOUND).contains(&n) {
+        Some(Number::Integer(n as i64))
+    } else {
+        BigInt::from_f64(n).map(Number::BigInteger)
+    }
+}
+
+fn compare_integer_float(i: i64, n: f64) -> Option<Ordering> {
+    if n.is_nan() {
+        None
+    } else if INTEGER_BOUND <= n {
+        Some(Ordering::Less)
+    } else if n < -INTEGER_BOUND {
+        Some(Ordering::Greater)
+    } else {
+        // Within the bounds the integral part of `n` is an integer, and the fractional part breaks a tie.
+        match i.cmp(&(n.trunc() as i64)) {
+            Ordering::Equal => 0.0.partial_cmp(&n.fract()),
+
+            ordering => Some(ordering),
+        }
+    }
+}
+
+fn compare_big_float(b: &BigInt, n: f64) -> Option<Ordering> {
+    match BigInt::from_f64(n.trunc()) {
+        Some(integral) => match b.cmp(&integral) {
+            Ordering::Equal => 0.0.partial_cmp(&n.fract()),
+
+            ordering => Some(ordering),
+        },
+
+        None if n.is_nan() => None,
+
+        None if n < 0.0 => Some(Ordering::Greater),
+
+        None => Some(Ordering::Less),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn integer_float_equality() {
+        assert_eq!(Number::Integer(1), Number::Float(1.0));
+        assert_eq!(Number::Integer(0), Number::Float(-0.0));
+        assert_ne!(Number::Integer(1), Number::Float(1.5));
+        assert_ne!(Number::Integer(1), Number::Float(f64::NAN));
+
+        // 2^53 + 1 has no float representation, and rounds to 2^53.
+        let beyond = (1_i64 << 53) + 1;
+        assert_ne!(Number::Integer(beyond), Number::Float(beyond as f64));
+        assert_eq!(Number::Integer(1 << 53), Number::Float(beyond as f64));
+
+        assert_ne!(Number::Integer(i64::MAX), Number::Float(INTEGER_BOUND));
+
+        let bound = Number::from(BigInt::from(i64::MAX).add(&BigInt::from(1)));
+        assert_eq!(bound, Number::Float(INTEGER_BOUND));
+        assert_ne!(bound, Number::Float(f64::INFINITY));
+    }
+
+    #[test]
+    fn integer_float_ordering() {
+        use Ordering::*;
+
+        assert_eq!(Number::Integer(1).compare(&Number::Float(1.5)), Some(Less));
+        assert_eq!(
+            Number::Float(-1.5).compare(&Number::Integer(-1)),
+            Some(Less)
+        );
+        assert_eq!(
+            Number::Integer(-1).compare(&Number::Float(-1.5)),
+            Some(Greater)
+        );
+        assert_eq!(
+            Number::Integer(i64::MAX).compare(&Number::Float(f64::INFINITY)),
+            Some(Less)
+        );
+        assert_eq!(Number::Integer(0).compare(&Number::Float(f64::NAN)), None);
+
+        let big = Number::from(BigInt::from(i64::MAX).mul(&BigInt::from(4)));
+        assert_eq!(big.compare(&Number::Integer(i64::MAX)), Some(Greater));
+        assert_eq!(Number::Float(1e300).compare(&big), Some(Greater));
+        assert_eq!(
+            big.compare(&Number::Float(f64::NEG_INFINITY)),
+            Some(Greater)
+        );
+    }
+
+    #[test]
+    fn integral_floats() {
+        assert_eq!(integral_float(3.0), Some(Number::Integer(3)));
+        assert_eq!(integral_float(-0.0), Some(Number::Integer(0)));
+        assert_eq!(integral_float(3.5), None);
+        assert!(matches!(
+            integral_float(INTEGER_BOUND),
+            Some(Number::BigInteger(_))
+        ));
+        assert!(matches!(
+            integral_float(-INTEGER_BOUND),
+            Some(Number::Integer(i64::MIN))
+        ));
+        assert_eq!(integral_float(f64::NAN), None);
+        assert_eq!(integral_float(f64::INFINITY), None);
+    }
+}
@@ -2,6 +2,7 @@
 pub enum OpOne {
     Minus,
     Bang,
+    Tilde,
 }
 
 impl std::fmt::Display for OpOne {
@@ -10,6 +11,8 @@ impl std::fmt::Display for OpOne {
             Self::Bang => write!(f, "!"),
 
             Self::Minus => write!(f, "-"),
+
+            Self::Tilde => write!(f, "~"),
         }
     }
 }
@@ -26,6 +29,14 @@ pub enum OpTwo {
     Minus,
     Star,
     Slash,
+    Percent,
+    TildeSlash,
+    StarStar,
+    Ampersand,
+    Pipe,
+    Caret,
+    LessLess,
+    GreaterGreater,
 }
 
 impl std::fmt::Display for OpTwo {
@@ -41,6 +52,14 @@ impl std::fmt::Display for OpTwo {
             Self::Minus => write!(f, "-"),
             Self::Star => write!(f, "*"),
             Self::Slash => write!(f, "/"),
+            Self::Percent => write!(f, "%"),
+            Self::TildeSlash => write!(f, "~/"),
+            Self::StarStar => write!(f, "**"),
+            Self::Ampersand => write!(f, "&"),
+            Self::Pipe => write!(f, "|"),
+            Self::Caret => write!(f, "^"),
+            Self::LessLess => write!(f, "<<"),
+            Self::GreaterGreater => write!(f, ">>"),
         }
     }
 }
@@ -0,0 +1,283 @@
+use std::rc::Rc;
+
+use crate::interpreter::natives::Native;
+
+use super::{BigInt, Closure, ExprB, ListHandle, MapHandle, Number};
+
+/// A compact representation of a value, of at most sixteen bytes.
+///
+/// Nil, booleans, integers, and floats are held inline, as are the handles of functions, lists, and maps, each a single pointer.
+/// Big integers, native functions, ranges, and strings are wider than a pointer, and so are boxed.
+/// A boxed value is read from a reference without cloning the box, and is replaced by a value of the same kind within the box.
+/// Values are not NaN-boxed into eight bytes, as an integer is a full 64 bits and so has no room alongside the bits of a NaN.
+///
+/// Environments hold the values of variables as values, and a value converts to and from an [`ExprB`], with lists, maps, and functions keeping their identity.
+#[derive(Clone, Debug)]
+pub enum Value {
+    Nil,
+
+    Boolean(bool),
+
+    Integer(i64),
+
+    Float(f64),
+
+    BigInteger(Box<BigInt>),
+
+    Lambda(Rc<Closure>),
+
+    List(ListHandle),
+
+    Map(MapHandle),
+
+    Native(Box<Native>),
+
+    Range(Box<Bounds>),
+
+    String(Box<Rc<str>>),
+}
+
+const _: () = assert!(std::mem::size_of::<Value>() <= 16);
+
+/// The bounds of a range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub start: i64,
+    pub end: i64,
+    pub inclusive: bool,
+}
+
+impl Value {
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Nil => false,
+
+            Value::Boolean(b) => *b,
+
+            _ => true,
+        }
+    }
+
+    /// The name of the type of the value, as used in type errors.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Nil => "nil",
+
+            Value::Boolean(_) => "boolean",
+
+            Value::Integer(_) | Value::BigInteger(_) => "integer",
+
+            Value::Float(_) => "float",
+
+            Value::Lambda(_) | Value::Native(_) => "function",
+
+            Value::List(_) => "list",
+
+            Value::Map(_) => "map",
+
+            Value::Range(_) => "range",
+
+            Value::String(_) => "string",
+        }
+    }
+
+    /// Set the value to `expr`, returning the previous value.
+    ///
+    /// Where the previous value is a string, native function, or range, and so is boxed, a value of the same kind is held in the same box.
+    pub fn replace(&mut self, expr: ExprB) -> ExprB {
+        match (self, expr) {
+            (Value::String(held), ExprB::String { s }) => ExprB::String {
+                s: std::mem::replace(held.as_mut(), s),
+            },
+
+            (Value::Native(held), ExprB::Native { native }) => ExprB::Native {
+                native: std::mem::replace(held.as_mut(), native),
+            },
+
+            (
+                Value::Range(held),
+                ExprB::Range {
+                    start,
+                    end,
+                    inclusive,
+                },
+            ) => {
+                let previous = std::mem::replace(
+                    held.as_mut(),
+                    Bounds {
+                        start,
+                        end,
+                        inclusive,
+                    },
+                );
+
+                ExprB::Range {
+                    start: previous.start,
+                    end: previous.end,
+                    inclusive: previous.inclusive,
+                }
+            }
+
+            (value, expr) => ExprB::from(std::mem::replace(value, Value::from(expr))),
+        }
+    }
+
+    /// The value as a number, if the value is an integer or a float.
+    pub fn number(&self) -> Option<Number> {
+        match self {
+            Value::BigInteger(i) => Some(Number::BigInteger(i.as_ref().clone())),
+
+            Value::Integer(i) => Some(Number::Integer(*i)),
+
+            Value::Float(n) => Some(Number::Float(*n)),
+
+            _ => None,
+        }
+    }
+}
+
+impl From<ExprB> for Value {
+    fn from(expr: ExprB) -> Self {
+        match expr {
+            ExprB::Nil => Value::Nil,
+
+            ExprB::Boolean { b } => Value::Boolean(b),
+
+            ExprB::Integer { i } => Value::Integer(i),
+
+            ExprB::Numeric { n } => Value::Float(n),
+
+            ExprB::BigInteger { i } => Value::BigInteger(Box::new(i)),
+
+            ExprB::Lambda { closure } => Value::Lambda(closure),
+
+            ExprB::List { l } => Value::List(l),
+
+            ExprB::Map { m } => Value::Map(m),
+
+            ExprB::Native { native } => Value::Native(Box::new(native)),
+
+            ExprB::Range {
+                start,
+                end,
+                inclusive,
+            } => Value::Range(Box::new(Bounds {
+                start,
+                end,
+                inclusive,
+            })),
+
+            ExprB::String { s } => Value::String(Box::new(s)),
+        }
+    }
+}
+
+impl From<Value> for ExprB {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Nil => ExprB::Nil,
+
+            Value::Boolean(b) => ExprB::mk_bool(b),
+
+            Value::Integer(i) => ExprB::mk_integer(i),
+
+            Value::Float(n) => ExprB::mk_numeric(n),
+
+            Value::BigInteger(i) => ExprB::BigInteger { i: *i },
+
+            Value::Lambda(closure) => ExprB::Lambda { closure },
+
+            Value::List(l) => ExprB::List { l },
+
+            Value::Map(m) => ExprB::Map { m },
+
+            Value::Native(native) => ExprB::Native { native: *native },
+
+            Value::Range(bounds) => ExprB::Range {
+                start: bounds.start,
+                end: bounds.end,
+                inclusive: bounds.inclusive,
+            },
+
+            Value::String(s) => ExprB::String { s: *s },
+        }
+    }
+}
+
+/// The expression of a borrowed value, cloning what the value holds rather than the value, so a boxed value is not boxed again.
+impl From<&Value> for ExprB {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Nil => ExprB::Nil,
+
+            Value::Boolean(b) => ExprB::mk_bool(*b),
+
+            Value::Integer(i) => ExprB::mk_integer(*i),
+
+            Value::Float(n) => ExprB::mk_numeric(*n),
+
+            Value::BigInteger(i) => ExprB::BigInteger {
+                i: i.as_ref().clone(),
+            },
+
+            Value::Lambda(closure) => ExprB::Lambda {
+                closure: closure.clone(),
+            },
+
+            Value::List(l) => ExprB::List { l: l.clone() },
+
+            Value::Map(m) => ExprB::Map { m: m.clone() },
+
+            Value::Native(native) => ExprB::Native {
+                native: native.as_ref().clone(),
+            },
+
+            Value::Range(bounds) => ExprB::Range {
+                start: bounds.start,
+                end: bounds.end,
+                inclusive: bounds.inclusive,
+            },
+
+            Value::String(s) => ExprB::String {
+                s: s.as_ref().clone(),
+            },
+        }
+    }
+}
+
+/// Equality of values, as with the equality of expressions.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        use Value::*;
+
+        match (self, other) {
+            (Nil, Nil) => true,
+
+            (Boolean(l), Boolean(r)) => l == r,
+
+            (BigInteger(_) | Integer(_) | Float(_), BigInteger(_) | Integer(_) | Float(_)) => {
+                self.number() == other.number()
+            }
+
+            (String(l), String(r)) => l == r,
+
+            (List(l), List(r)) => Rc::ptr_eq(l, r),
+
+            (Map(l), Map(r)) => Rc::ptr_eq(l, r),
+
+            (Lambda(l), Lambda(r)) => Rc::ptr_eq(l, r),
+
+            (Native(l), Native(r)) => l.name == r.name && l.receiver == r.receiver,
+
+            (Range(l), Range(r)) => l == r,
+
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", ExprB::from(self))
+    }
+}
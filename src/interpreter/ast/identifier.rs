@@ -1,21 +1,102 @@
-pub type Id = String;
+use std::rc::Rc;
+
+/// A name, interned by the interner of a walker so each distinct name is held once.
+///
+/// As equal names share a string, symbols are compared and hashed by the address of the string rather than by its characters.
+/// And so, symbols are only compared with symbols of the same walker.
+#[derive(Clone)]
+pub struct Id(Rc<str>);
+
+impl Id {
+    /// The symbol of `name`, an interned string.
+    pub(crate) fn interned(name: Rc<str>) -> Self {
+        Id(name)
+    }
+
+    /// The shared string of the name.
+    pub fn string(&self) -> Rc<str> {
+        self.0.clone()
+    }
+}
+
+impl std::ops::Deref for Id {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl PartialEq for Id {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Id {}
+
+// Symbols are ordered by name, which is consistent with equality as equal names share a string.
+impl Ord for Id {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl PartialOrd for Id {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::hash::Hash for Id {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::ptr::hash(Rc::as_ptr(&self.0), state)
+    }
+}
+
+impl std::fmt::Debug for Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", &*self.0)
+    }
+}
+
+impl std::fmt::Display for Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Where the value of a variable is found, relative to the environment the variable is used in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Address {
+    /// The number of environments to pass through to reach the environment the variable is declared in.
+    pub offset: usize,
+
+    /// The slot of the variable within the environment the variable is declared in.
+    pub slot: usize,
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Identifier {
-    pub name: String,
-    pub offset: Option<usize>,
+    pub name: Id,
+    pub address: Option<Address>,
 }
 
 impl Identifier {
-    pub fn fresh(name: String, offset: Option<usize>) -> Self {
-        Identifier { name, offset }
+    pub fn fresh(name: Id, address: Option<Address>) -> Self {
+        Identifier { name, address }
+    }
+
+    /// An identifier declared in the environment it is used in, at `slot`.
+    pub fn local(name: Id, slot: usize) -> Self {
+        Identifier::fresh(name, Some(Address { offset: 0, slot }))
     }
 }
 
 impl std::fmt::Display for Identifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.offset {
-            Some(x) => write!(f, "{}[{x}]", self.name),
+        match self.address {
+            Some(Address { offset, slot }) => write!(f, "{}[{offset}:{slot}]", self.name),
             None => write!(f, "{}[-]", self.name),
         };
         Ok(())
@@ -27,7 +108,7 @@ impl Identifier {
         &self.name
     }
 
-    pub fn offset(&self) -> Option<usize> {
-        self.offset
+    pub fn address(&self) -> Option<Address> {
+        self.address
     }
 }
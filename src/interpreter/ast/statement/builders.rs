@@ -1,8 +1,14 @@
 use crate::interpreter::{
     Statement,
-    ast::{expression::Expr, identifier::Identifier},
+    ast::{
+        expression::Expr,
+        identifier::{Id, Identifier},
+    },
+    location::Location,
 };
 
+use std::rc::Rc;
+
 use super::Statements;
 
 impl Statement {
@@ -38,19 +44,69 @@ impl Statement {
         }
     }
 
-    pub fn mk_loop(statements: Vec<Statement>) -> Self {
-        Statement::Loop { statements }
+    pub fn mk_loop(label: Option<Id>, statements: Vec<Statement>) -> Self {
+        Statement::Loop { label, statements }
     }
 
-    pub fn mk_while(condition: Expr, body: Statements) -> Self {
-        Statement::While { condition, body }
+    pub fn mk_while(
+        label: Option<Id>,
+        condition: Expr,
+        body: Statements,
+        increment: Option<Expr>,
+    ) -> Self {
+        Statement::While {
+            label,
+            condition,
+            body,
+            increment,
+        }
+    }
+
+    pub fn mk_for_in(
+        location: Location,
+        label: Option<Id>,
+        id: Identifier,
+        iterable: Expr,
+        body: Statements,
+    ) -> Self {
+        Statement::ForIn {
+            location,
+            label,
+            id,
+            iterable,
+            body,
+        }
+    }
+
+    pub fn mk_break(label: Option<Id>) -> Self {
+        Statement::Break { label }
+    }
+
+    pub fn mk_continue(label: Option<Id>) -> Self {
+        Statement::Continue { label }
     }
 
     pub fn mk_function(head: Identifier, args: Vec<Identifier>, body: Statements) -> Self {
         Statement::Function {
             id: head,
-            parameters: args,
+            parameters: args.into(),
+            body: Rc::new(body),
+        }
+    }
+
+    pub fn mk_throw(location: Location, e: Expr) -> Self {
+        Statement::Throw { location, e }
+    }
+
+    pub fn mk_try(
+        body: Statements,
+        catch: Option<(Identifier, Statements)>,
+        finally: Option<Statements>,
+    ) -> Self {
+        Statement::Try {
             body,
+            catch,
+            finally,
         }
     }
 }
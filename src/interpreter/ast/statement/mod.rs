@@ -1,4 +1,12 @@
-use crate::interpreter::ast::{expression::Expr, identifier::Identifier};
+use std::rc::Rc;
+
+use crate::interpreter::{
+    ast::{
+        expression::Expr,
+        identifier::{Id, Identifier},
+    },
+    location::Location,
+};
 
 mod builders;
 
@@ -15,7 +23,9 @@ pub enum Statement {
         statements: Vec<Statement>,
     },
 
-    Break,
+    Break {
+        label: Option<Id>,
+    },
 
     Conditional {
         condition: Expr,
@@ -28,18 +38,32 @@ pub enum Statement {
         e: Expr,
     },
 
+    Continue {
+        label: Option<Id>,
+    },
+
     Empty,
 
     Expression {
         e: Expr,
     },
 
-    Function {
+    /// A loop over the values of `iterable`, from the statement at `location`.
+    ForIn {
+        location: Location,
+        label: Option<Id>,
         id: Identifier,
-        parameters: Vec<Identifier>,
+        iterable: Expr,
         body: Statements,
     },
 
+    /// A function declaration, with parameters and body shared by each closure of the declaration.
+    Function {
+        id: Identifier,
+        parameters: Rc<[Identifier]>,
+        body: Rc<Statements>,
+    },
+
     Print {
         e: Expr,
     },
@@ -48,12 +72,31 @@ pub enum Statement {
         expr: Expr,
     },
 
+    /// A throw of the value of `e`, from the statement at `location`.
+    Throw {
+        location: Location,
+        e: Expr,
+    },
+
+    /// A `try` of `body`, with errors caught by `catch` and `finally` interpreted on any exit from `body` or `catch` other than an uncatchable error.
+    ///
+    /// A caught error is bound to the identifier of `catch` within the statements of `catch`.
+    Try {
+        body: Statements,
+        catch: Option<(Identifier, Statements)>,
+        finally: Option<Statements>,
+    },
+
+    /// A loop over `body` while `condition` holds, evaluating `increment` after each iteration, including those ended by a continue.
     While {
+        label: Option<Id>,
         condition: Expr,
         body: Statements,
+        increment: Option<Expr>,
     },
 
     Loop {
+        label: Option<Id>,
         statements: Vec<Statement>,
     },
 }
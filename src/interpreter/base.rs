@@ -1,5 +1,22 @@
+use crate::interpreter::{err::Frame, gc::Heap};
+
 pub struct Base<'s> {
     pub stdio: Box<dyn std::io::Write + 's>,
+
+    /// The calls in progress, outermost first.
+    pub frames: Vec<Frame>,
+
+    /// The address of the stack of the host at the outermost call in progress.
+    pub stack_base: usize,
+
+    /// The number of steps of evaluation taken.
+    pub steps: u64,
+
+    /// The number of bytes allocated for strings, lists, maps, and environments, since last measured as live.
+    pub allocated: usize,
+
+    /// The environments, lists, and maps created by evaluation.
+    pub heap: Heap,
 }
 
 impl<'s> Base<'s> {
@@ -12,6 +29,11 @@ impl Default for Base<'_> {
     fn default() -> Self {
         Base {
             stdio: Box::new(std::io::stdout()),
+            frames: Vec::default(),
+            stack_base: 0,
+            steps: 0,
+            allocated: 0,
+            heap: Heap::default(),
         }
     }
 }
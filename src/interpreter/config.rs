@@ -0,0 +1,59 @@
+use std::{
+    sync::{Arc, atomic::AtomicBool},
+    time::Instant,
+};
+
+/// Options which alter the behaviour of an interpreter.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Whether strings are converted to numbers when used as the operand of a numeric operator, so `-"64"` is `-64.0`.
+    ///
+    /// Without coercion, a numeric operator applied to a string is a type error.
+    pub string_coercion: bool,
+
+    /// The greatest number of calls which may be in progress at once, beyond which a call is a stack overflow.
+    ///
+    /// Calls are interpreted on the stack of the host thread, so the depth reached is also bounded by `max_stack`.
+    pub max_call_depth: usize,
+
+    /// The greatest number of bytes of the stack of the host thread which calls in progress may use, beyond which a call is a stack overflow.
+    ///
+    /// The stack used by a call varies with its body and with the build, so the stack is measured rather than estimated from the depth of calls.
+    /// The default of one megabyte fits within the stack of two megabytes given to a spawned thread, with room for the call in progress.
+    /// An embedder which raises `max_stack` must also raise the stack size of the thread which interprets, as otherwise the host may overflow before a stack overflow is found.
+    pub max_stack: usize,
+
+    /// The greatest number of steps of evaluation, if any, beyond which evaluation terminates.
+    pub max_steps: Option<u64>,
+
+    /// The greatest number of bytes, if any, which may be allocated for strings, lists, maps, and environments, beyond which evaluation terminates.
+    ///
+    /// Bytes are counted as allocated, and when the quota would be exceeded the bytes held by live values and environments are measured, returning the bytes since freed to the quota.
+    pub max_memory: Option<usize>,
+
+    /// The instant, if any, after which evaluation terminates.
+    pub deadline: Option<Instant>,
+
+    /// A flag, if any, which when set from any thread terminates evaluation.
+    pub cancel: Option<Arc<AtomicBool>>,
+
+    /// Whether statements are optimised as they are parsed, by folding constant expressions and removing statements which are never interpreted.
+    ///
+    /// An expression which would stumble is left as parsed, so the stumble is found at the same location when interpreted.
+    pub optimise: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            string_coercion: false,
+            max_call_depth: 512,
+            max_stack: 1 << 20,
+            max_steps: None,
+            max_memory: None,
+            deadline: None,
+            cancel: None,
+            optimise: false,
+        }
+    }
+}
@@ -1,17 +1,31 @@
-use std::{cell::RefCell, collections::HashMap, mem::swap, rc::Rc};
-
-use super::ast::{
-    expression::ExprB,
-    identifier::{Id, Identifier},
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use super::{
+    ast::{
+        expression::{ExprB, Value},
+        identifier::{Address, Id, Identifier},
+    },
+    interner::Interner,
+    natives,
 };
 
-pub type Assignments = HashMap<Id, ExprB>;
+/// The slot of each name declared in an environment.
+pub type Slots = HashMap<Id, usize>;
 
 pub type EnvHandle = Rc<RefCell<Env>>;
 
-#[derive(Clone, Debug)]
+/// An environment of variables, each held in a slot.
+///
+/// While parsing, names are declared in an environment, and each use of a variable is resolved to an address of the slot of the declaration.
+/// While interpreting, values are held in and found by slot, with no need for the names of the slots.
+/// Values are held compactly, as a [`Value`], and so are converted to and from an [`ExprB`] as held and found.
+#[derive(Clone)]
 pub struct Env {
-    assignments: Assignments,
+    slots: Slots,
+
+    /// The value of each slot, if assigned.
+    values: Vec<Option<Value>>,
+
     enclosing: Option<EnvHandle>,
 
     depth: usize,
@@ -25,7 +39,8 @@ pub enum EnvErr {
 impl Default for Env {
     fn default() -> Self {
         Env {
-            assignments: Assignments::default(),
+            slots: Slots::default(),
+            values: Vec::default(),
             enclosing: None,
 
             depth: 0,
@@ -38,8 +53,10 @@ impl Env {
         Rc::new(RefCell::new(Env::default()))
     }
 
-    pub fn fresh_std_env() -> EnvHandle {
-        let global = Rc::new(RefCell::new(Env::default()));
+    /// A fresh environment enclosed by a global environment of the natives, with names interned by `interner`.
+    pub(crate) fn fresh_std_env(interner: &mut Interner) -> EnvHandle {
+        let global = Env::fresh_global_handle();
+        natives::register(&mut global.borrow_mut(), interner);
         Env::narrow(global)
     }
 
@@ -48,7 +65,8 @@ impl Env {
         let enclosing = Some(handle.clone());
 
         let narrow_env = Env {
-            assignments: Assignments::default(),
+            slots: Slots::default(),
+            values: Vec::default(),
             enclosing,
             depth,
         };
@@ -63,84 +81,145 @@ impl Env {
     pub fn depth(&self) -> usize {
         self.depth
     }
+
+    /// The number of bytes held by the environment and its slots, without the bytes held by each value.
+    pub fn size(&self) -> usize {
+        std::mem::size_of::<Env>() + self.values.len() * std::mem::size_of::<Option<Value>>()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.values.iter().flatten()
+    }
+
+    /// Drop each value and the enclosing environment.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.enclosing = None;
+    }
 }
 
 impl Env {
-    pub fn insert(&mut self, id: impl std::borrow::Borrow<Id>, v: ExprB) -> Option<ExprB> {
-        self.assignments.insert(id.borrow().clone(), v)
+    /// The slot of `id`, with a fresh slot for `id` if `id` has not been declared.
+    pub fn declare(&mut self, id: &Id) -> usize {
+        let fresh = self.slots.len();
+        *self.slots.entry(id.clone()).or_insert(fresh)
     }
 
-    pub fn assign(&mut self, id: &Id, mut v: ExprB) -> Result<ExprB, EnvErr> {
-        match self.assignments.get_mut(id) {
-            Some(expr) => {
-                swap(expr, &mut v);
-                Ok(v)
+    /// Declare `id` and set the value of the slot of `id` to `v`, returning the previous value of the slot, if any.
+    pub fn insert(&mut self, id: &Id, v: ExprB) -> Option<ExprB> {
+        let slot = self.declare(id);
+        self.define(slot, v)
+    }
+
+    /// Set the value of `slot` to `v`, returning the previous value of the slot, if any.
+    pub fn define(&mut self, slot: usize, v: ExprB) -> Option<ExprB> {
+        if self.values.len() <= slot {
+            self.values.resize(slot + 1, None);
+        }
+
+        match &mut self.values[slot] {
+            Some(value) => Some(value.replace(v)),
+
+            empty => {
+                *empty = Some(Value::from(v));
+                None
             }
+        }
+    }
 
-            None => match &self.enclosing {
-                Some(e) => e.borrow_mut().assign(id, v),
+    /// Set the value of the variable `id` to `v`, returning the previous value, if the variable has been assigned a value.
+    pub fn assign(&mut self, id: &Identifier, v: ExprB) -> Result<ExprB, EnvErr> {
+        let Some(Address { offset, slot }) = id.address() else {
+            return Err(EnvErr::MissingAsignee);
+        };
+
+        let assign = |env: &mut Env| match env.values.get_mut(slot) {
+            Some(Some(value)) => Ok(value.replace(v)),
+
+            _ => Err(EnvErr::MissingAsignee),
+        };
+
+        match offset {
+            0 => assign(self),
+
+            _ => match self.ancestor(offset) {
+                Some(env) => assign(&mut env.borrow_mut()),
 
                 None => Err(EnvErr::MissingAsignee),
             },
         }
     }
 
+    /// The value of the variable `id`, if the variable has been assigned a value.
     pub fn get(&self, id: &Identifier) -> Option<ExprB> {
-        match id.offset() {
-            None => None,
-
-            Some(0) => self.assignments.get(id.name()).cloned(),
-
-            Some(mut o) => {
-                let mut ee = self.enclosing();
-                while 1 < o {
-                    ee = ee.expect("! Missing env").borrow_mut().enclosing();
-                    o -= 1;
-                }
-
-                ee.expect("X_X")
-                    .borrow_mut()
-                    .assignments
-                    .get(id.name())
-                    .cloned()
-            }
+        let Address { offset, slot } = id.address()?;
+
+        match offset {
+            0 => self.values.get(slot)?.as_ref().map(ExprB::from),
+
+            _ => self
+                .ancestor(offset)?
+                .borrow()
+                .values
+                .get(slot)?
+                .as_ref()
+                .map(ExprB::from),
         }
     }
 
-    pub fn offset(&self, id: &Id) -> Option<usize> {
-        match self.assignments.get(id) {
-            Some(_) => Some(0),
+    /// The environment `offset` environments out from this environment, for an offset of at least one.
+    fn ancestor(&self, offset: usize) -> Option<EnvHandle> {
+        let mut ancestor = self.enclosing()?;
+
+        for _ in 1..offset {
+            let enclosing = ancestor.borrow().enclosing()?;
+            ancestor = enclosing;
+        }
 
-            None => {
-                let mut offset = 1;
-                let mut enclosing_env = self.enclosing();
+        Some(ancestor)
+    }
 
-                loop {
-                    match &enclosing_env {
-                        Some(ex) => {
-                            if ex.borrow().assignments.contains_key(id) {
-                                return Some(offset);
-                            }
-                        }
+    /// The address of the declaration of `id` visible from this environment, if any.
+    pub fn resolve(&self, id: &Id) -> Option<Address> {
+        if let Some(&slot) = self.slots.get(id) {
+            return Some(Address { offset: 0, slot });
+        }
 
-                        None => return None,
-                    }
+        let mut offset = 1;
+        let mut enclosing_env = self.enclosing();
 
-                    offset += 1;
-                    enclosing_env = enclosing_env.unwrap().borrow().enclosing();
-                }
+        while let Some(env) = enclosing_env {
+            if let Some(&slot) = env.borrow().slots.get(id) {
+                return Some(Address { offset, slot });
             }
+
+            offset += 1;
+            enclosing_env = env.borrow().enclosing();
         }
+
+        None
     }
 }
 
 impl std::fmt::Display for Env {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Env @ {}", self.depth);
-        for key in self.assignments.keys() {
+        for key in self.slots.keys() {
             writeln!(f, "\t{key}");
         }
 
         Ok(())
     }
 }
+
+// Closures hold the environment they are defined in, and so may be found within their own environment.
+// To avoid following such a cycle, only the names and the number of slots of the environment are written.
+impl std::fmt::Debug for Env {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Env")
+            .field("depth", &self.depth)
+            .field("names", &self.slots.keys().collect::<Vec<_>>())
+            .field("slots", &self.values.len())
+            .finish()
+    }
+}
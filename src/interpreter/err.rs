@@ -1,24 +1,56 @@
-use super::{TreeWalker, environment::EnvErr, location::Location, scanner::token::TknK};
+use std::rc::Rc;
 
-#[derive(Debug, PartialEq, PartialOrd)]
+use super::{
+    TreeWalker,
+    ast::expression::{ExprB, Map, MapKey},
+    environment::EnvErr,
+    location::Location,
+    scanner::token::TknK,
+};
+
+#[derive(Debug, PartialEq)]
 pub struct Stumble {
     pub location: Location,
     pub kind: StumbleKind,
+
+    /// Whether the location was set by a located expression, rather than taken from the parser.
+    pub located: bool,
+
+    /// The calls in progress when the stumble occurred, outermost first.
+    pub trace: Vec<Frame>,
+}
+
+/// A call of a function, with the location of the call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub name: Rc<str>,
+    pub location: Location,
 }
 
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug, PartialEq)]
+#[rustfmt::skip]
 pub enum StumbleKind {
     // Parsing
     ArgLimit,
 
+    BreakOutsideLoop,
+
+    ContinueOutsideLoop,
+
     ExpectedAssignment,
 
     ExpectedBlock,
 
+    ExpectedCatch,
+
     ExpectedFound { expected: TknK, found: TknK },
 
     ExpectedLambda,
 
+    ExpectedLoop,
+
+    ExpectedProperty,
+
     ForInitialiser,
 
     InvalidAsignee,
@@ -35,15 +67,66 @@ pub enum StumbleKind {
 
     Unexpected(usize),
 
+    UnknownLabel { label: String },
+
     // Evaluation
+    ArityMismatch { expected: usize, found: usize },
+
+    BitwiseOverflow,
+
+    /// Evaluation was cancelled by the host.
+    Cancelled,
+
     ConflictingSubexpression,
 
+    /// Evaluation continued past the deadline.
+    DeadlineExceeded,
+
+    DivisionByZero,
+
+    IndexOutOfBounds { index: usize, length: usize },
+
     InvalidConversion,
 
     InvalidAssignTo,
 
     InvalidIdentifier { id: String },
 
+    InvalidIndex,
+
+    IntegerOverflow,
+
+    InvalidShift,
+
+    NotIndexable,
+
+    NotIterable,
+
+    NotSafeIntegral,
+
+    /// Evaluation allocated more than the maximum number of bytes.
+    OutOfMemory { limit: usize },
+
+    /// A call beyond the maximum depth of calls, or beyond the stack reserved for calls.
+    StackOverflow { depth: usize },
+
+    /// Evaluation took more than the maximum number of steps.
+    StepLimit { steps: u64 },
+
+    /// A value thrown by a `throw` statement.
+    Thrown { value: ExprB },
+
+    /// An operator applied to operands of the wrong types, given by name, with no left operand for a unary operator.
+    TypeError {
+        op: String,
+        left: Option<&'static str>,
+        right: &'static str,
+    },
+
+    UnhashableKey,
+
+    UnknownProperty { name: String },
+
     // Tokens
     MissingAsignee,
 
@@ -51,13 +134,239 @@ pub enum StumbleKind {
 
     MultilineString,
 
+    UnterminatedInterpolation,
+
     Unrecognised { character: char },
 }
 
+impl std::fmt::Display for StumbleKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use StumbleKind::*;
+
+        match self {
+            ArgLimit => write!(f, "too many arguments"),
+
+            BreakOutsideLoop => write!(f, "break outside of a loop"),
+
+            ContinueOutsideLoop => write!(f, "continue outside of a loop"),
+
+            ExpectedAssignment => write!(f, "expected an assignment"),
+
+            ExpectedBlock => write!(f, "expected a block"),
+
+            ExpectedCatch => write!(f, "expected a catch or finally"),
+
+            ExpectedFound { expected, found } => {
+                write!(f, "expected {expected:?}, found {found:?}")
+            }
+
+            ExpectedLambda => write!(f, "only functions may be called"),
+
+            ExpectedLoop => write!(f, "expected a loop"),
+
+            ExpectedProperty => write!(f, "expected a property name"),
+
+            ForInitialiser => write!(f, "invalid for initialiser"),
+
+            InvalidAsignee => write!(f, "invalid assignment target"),
+
+            MismatchedParentheses => write!(f, "mismatched parentheses"),
+
+            MissingToken => write!(f, "missing token"),
+
+            OpenStatement => write!(f, "unterminated statement"),
+
+            Todo => write!(f, "unimplemented"),
+
+            TokensExhausted => write!(f, "unexpected end of input"),
+
+            Unexpected(_) => write!(f, "unexpected token"),
+
+            UnknownLabel { label } => write!(f, "unknown loop label '{label}'"),
+
+            ArityMismatch { expected, found } => {
+                write!(f, "expected {expected} arguments, found {found}")
+            }
+
+            BitwiseOverflow => write!(f, "bitwise overflow"),
+
+            Cancelled => write!(f, "evaluation cancelled"),
+
+            ConflictingSubexpression => write!(f, "operand of the wrong type"),
+
+            DeadlineExceeded => write!(f, "evaluation exceeded its deadline"),
+
+            DivisionByZero => write!(f, "division by zero"),
+
+            IndexOutOfBounds { index, length } => {
+                write!(f, "index {index} out of bounds for length {length}")
+            }
+
+            InvalidConversion => write!(f, "invalid conversion"),
+
+            InvalidAssignTo => write!(f, "invalid assignment target"),
+
+            InvalidIdentifier { id } => write!(f, "undefined variable '{id}'"),
+
+            InvalidIndex => write!(f, "invalid index"),
+
+            IntegerOverflow => write!(f, "integer overflow"),
+
+            InvalidShift => write!(f, "invalid shift amount"),
+
+            NotIndexable => write!(f, "value is not indexable"),
+
+            NotIterable => write!(f, "value is not iterable"),
+
+            NotSafeIntegral => write!(f, "number is not a safe integer"),
+
+            OutOfMemory { limit } => write!(f, "evaluation allocated more than {limit} bytes"),
+
+            StackOverflow { depth } => write!(f, "stack overflow, beyond {depth} calls"),
+
+            StepLimit { steps } => write!(f, "evaluation exceeded {steps} steps"),
+
+            Thrown { value } => write!(f, "uncaught {}", value.repr()),
+
+            TypeError { op, left, right } => match left {
+                Some(left) => write!(f, "unsupported operands for {op}: {left} and {right}"),
+
+                None => write!(f, "unsupported operand for {op}: {right}"),
+            },
+
+            UnhashableKey => write!(f, "unhashable map key"),
+
+            UnknownProperty { name } => write!(f, "unknown property '{name}'"),
+
+            MissingAsignee => write!(f, "assignment to an undeclared variable"),
+
+            TrailingDot => write!(f, "trailing dot"),
+
+            MultilineString => write!(f, "multiline string"),
+
+            UnterminatedInterpolation => write!(f, "unterminated interpolation"),
+
+            Unrecognised { character } => write!(f, "unrecognised character '{character}'"),
+        }
+    }
+}
+
+impl StumbleKind {
+    /// Whether the stumble may be caught by a `catch`, as is any stumble other than the termination of evaluation by the host.
+    pub fn is_catchable(&self) -> bool {
+        !matches!(
+            self,
+            StumbleKind::Cancelled
+                | StumbleKind::DeadlineExceeded
+                | StumbleKind::OutOfMemory { .. }
+                | StumbleKind::StepLimit { .. }
+        )
+    }
+}
+
 impl Stumble {
     pub fn kind(&self) -> &StumbleKind {
         &self.kind
     }
+
+    /// The stumble as a value, as bound by a `catch`.
+    ///
+    /// A thrown value is itself, and any other stumble is a map of a `message` describing the stumble and the `line` of the stumble, counted from one.
+    pub fn into_value(self) -> ExprB {
+        match self.kind {
+            StumbleKind::Thrown { value } => value,
+
+            kind => {
+                let mut error = Map::default();
+
+                error.insert(
+                    MapKey::String {
+                        s: "message".into(),
+                    },
+                    ExprB::mk_string(kind.to_string()),
+                );
+
+                error.insert(
+                    MapKey::String { s: "line".into() },
+                    ExprB::mk_integer(self.location.line as i64 + 1),
+                );
+
+                ExprB::mk_map(error)
+            }
+        }
+    }
+
+    /// The stumble located at `location`, unless already located by a more specific expression.
+    pub fn locate(self, location: Location) -> Self {
+        match self.located {
+            true => self,
+
+            false => Stumble {
+                location,
+                located: true,
+                ..self
+            },
+        }
+    }
+
+    /// The stumble with `trace`, unless already traced from a more deeply nested call.
+    pub fn traced(self, trace: &[Frame]) -> Self {
+        match self.trace.is_empty() {
+            true => Stumble {
+                trace: trace.to_vec(),
+                ..self
+            },
+
+            false => self,
+        }
+    }
+
+    /// The number of times a repeated frame is written before the repetitions are collapsed.
+    const REPEATS_WRITTEN: usize = 3;
+
+    /// The calls in progress when the stumble occurred, innermost first, each with the line of the call in progress within the function.
+    ///
+    /// For example, `at fib (line 3)`, `at main (line 10)`, `at script (line 12)`, with lines counted from one.
+    /// A frame repeated more than a few times in succession, as by deep recursion, is collapsed to `... repeated n more times`.
+    pub fn traceback(&self) -> Vec<String> {
+        let mut line = self.location.line;
+        let mut traceback: Vec<String> = Vec::default();
+        let mut repeats = 0;
+
+        for frame in self.trace.iter().rev() {
+            let entry = format!("at {} (line {})", frame.name, line + 1);
+            line = frame.location.line;
+
+            match traceback.last() == Some(&entry) {
+                true => repeats += 1,
+
+                false => {
+                    Self::collapse(&mut traceback, repeats);
+                    repeats = 0;
+                }
+            }
+
+            if repeats < Self::REPEATS_WRITTEN {
+                traceback.push(entry);
+            }
+        }
+
+        Self::collapse(&mut traceback, repeats);
+        traceback.push(format!("at script (line {})", line + 1));
+
+        traceback
+    }
+
+    /// Notes the repeats of the last frame of `traceback` which were not written.
+    fn collapse(traceback: &mut Vec<String>, repeats: usize) {
+        if Self::REPEATS_WRITTEN <= repeats {
+            match repeats + 1 - Self::REPEATS_WRITTEN {
+                1 => traceback.push("... repeated 1 more time".to_owned()),
+
+                collapsed => traceback.push(format!("... repeated {collapsed} more times")),
+            }
+        }
+    }
 }
 
 impl From<EnvErr> for StumbleKind {
@@ -73,6 +382,8 @@ impl TreeWalker {
         Stumble {
             location: self.parse_location,
             kind: kind.into(),
+            located: false,
+            trace: Vec::default(),
         }
     }
 
@@ -95,7 +406,12 @@ impl TreeWalker {
             },
         };
 
-        Stumble { location, kind }
+        Stumble {
+            location,
+            kind,
+            located: false,
+            trace: Vec::default(),
+        }
     }
 
     pub fn handle_stumble(&self, stumble: &Stumble) {
@@ -110,17 +426,19 @@ impl TreeWalker {
 
         let kind = &stumble.kind;
 
-        match stumble.kind {
-            StumbleKind::Unexpected(tkn) => {
-                println!("{:?}", self.tokens[tkn])
-            }
-
-            _ => {}
+        if let StumbleKind::Unexpected(tkn) = stumble.kind {
+            println!("{:?}", self.tokens[tkn])
         }
 
         let error_line: String = self.source[src_start..src_end].iter().collect();
 
-        println!("Error on line {line} at column {col}: {kind:?}");
+        println!("Error on line {} at column {col}: {kind}", line + 1);
         println!("> {error_line}");
+
+        if !stumble.trace.is_empty() {
+            for frame in stumble.traceback() {
+                println!("  {frame}");
+            }
+        }
     }
 }
@@ -0,0 +1,209 @@
+use crate::interpreter::{
+    ast::expression::{BigInt, Number, OpTwo},
+    err::StumbleKind,
+};
+
+/// The most bits a power of big integers is computed to, as the time taken to compute a power grows with the square of its size.
+pub const MAX_POWER_BITS: u64 = 1 << 16;
+
+/// The result of applying the arithmetic operator `op` to `l` and `r`.
+///
+/// An operation on two integers results in an integer, with the exception of `/`, which always results in a float.
+/// An integer result which overflows an i64 is a big integer, and a big integer result within the range of an i64 is an i64.
+/// Otherwise, an integer operand is promoted to a float and the operation results in a float.
+pub fn arithmetic(op: OpTwo, l: Number, r: Number) -> Result<Number, StumbleKind> {
+    match (l, r) {
+        (Number::Integer(l), Number::Integer(r)) => match integer_arithmetic(op, l, r)? {
+            Some(n) => Ok(n),
+
+            None => big_arithmetic(op, &BigInt::from(l), &BigInt::from(r)),
+        },
+
+        (l, r) => match (l.to_big(), r.to_big()) {
+            (Some(l), Some(r)) => big_arithmetic(op, &l, &r),
+
+            _ => Ok(Number::Float(float_arithmetic(op, l.to_f64(), r.to_f64()))),
+        },
+    }
+}
+
+/// The negation of `n`.
+pub fn negate(n: Number) -> Number {
+    match n {
+        Number::Integer(i) => match i.checked_neg() {
+            Some(negation) => Number::Integer(negation),
+
+            None => Number::BigInteger(BigInt::from(i).neg()),
+        },
+
+        Number::BigInteger(b) => Number::from(b.neg()),
+
+        Number::Float(n) => Number::Float(-n),
+    }
+}
+
+/// The result of `op` on integers, or none if the result overflows an i64.
+fn integer_arithmetic(op: OpTwo, l: i64, r: i64) -> Result<Option<Number>, StumbleKind> {
+    use OpTwo::*;
+
+    let result = match op {
+        Plus => l.checked_add(r),
+
+        Minus => l.checked_sub(r),
+
+        Star => l.checked_mul(r),
+
+        Slash => return Ok(Some(Number::Float(l as f64 / r as f64))),
+
+        // The remainder of floored division, and so with the sign of the divisor.
+        Percent => {
+            if r == 0 {
+                return Err(StumbleKind::DivisionByZero);
+            }
+
+            let remainder = l.wrapping_rem(r);
+            match remainder != 0 && (remainder < 0) != (r < 0) {
+                true => Some(remainder + r),
+                false => Some(remainder),
+            }
+        }
+
+        // Division rounded towards negative infinity, so `l == (l ~/ r) * r + l % r`.
+        TildeSlash => {
+            if r == 0 {
+                return Err(StumbleKind::DivisionByZero);
+            }
+
+            match l.wrapping_rem(r) != 0 && (l < 0) != (r < 0) {
+                true => l.checked_div(r).map(|q| q - 1),
+                false => l.checked_div(r),
+            }
+        }
+
+        StarStar => match u32::try_from(r) {
+            Ok(r) => l.checked_pow(r),
+
+            Err(_) => None,
+        },
+
+        _ => unreachable!("{op:?} is not an arithmetic operator"),
+    };
+
+    Ok(result.map(Number::Integer))
+}
+
+fn big_arithmetic(op: OpTwo, l: &BigInt, r: &BigInt) -> Result<Number, StumbleKind> {
+    use OpTwo::*;
+
+    let result = match op {
+        Plus => l.add(r),
+
+        Minus => l.sub(r),
+
+        Star => l.mul(r),
+
+        Slash => return Ok(Number::Float(big_divide(l, r))),
+
+        Percent => match l.div_rem_floor(r) {
+            Some((_, remainder)) => remainder,
+
+            None => return Err(StumbleKind::DivisionByZero),
+        },
+
+        TildeSlash => match l.div_rem_floor(r) {
+            Some((quotient, _)) => quotient,
+
+            None => return Err(StumbleKind::DivisionByZero),
+        },
+
+        // A negative exponent results in a fraction.
+        StarStar if r.is_negative() => {
+            return Ok(Number::Float(l.to_f64().powf(r.to_f64())));
+        }
+
+        // The power has fewer than `exponent` times as many bits as the base, and so is estimated before being computed.
+        StarStar => match r.to_i64().and_then(|r| u32::try_from(r).ok()) {
+            Some(exponent) if l.bits().saturating_mul(exponent as u64) <= MAX_POWER_BITS => {
+                l.pow(exponent)
+            }
+
+            // Only a base of magnitude at most one has a power with such an exponent that is not impractically large.
+            _ => match l.to_i64() {
+                Some(0 | 1) => l.clone(),
+
+                Some(-1) => match r.div_rem_floor(&BigInt::from(2)) {
+                    Some((_, parity)) if parity.is_zero() => BigInt::from(1),
+                    _ => BigInt::from(-1),
+                },
+
+                _ => return Err(StumbleKind::IntegerOverflow),
+            },
+        },
+
+        _ => unreachable!("{op:?} is not an arithmetic operator"),
+    };
+
+    Ok(Number::from(result))
+}
+
+/// The most bits of a divisor converted to a float, well within the range of a float.
+const MAX_DIVISOR_BITS: u64 = 512;
+
+/// The float nearest `l / r`, which is finite whenever the quotient is within the range of a float.
+///
+/// The quotient of the magnitudes is found exactly as a whole part and a remainder, and the remainder and divisor are shifted down together before being converted, so neither converts to infinity.
+fn big_divide(l: &BigInt, r: &BigInt) -> f64 {
+    let magnitude = |i: &BigInt| if i.is_negative() { i.neg() } else { i.clone() };
+    let (l_magnitude, r_magnitude) = (magnitude(l), magnitude(r));
+
+    let Some((quotient, remainder)) = l_magnitude.div_rem_floor(&r_magnitude) else {
+        return l.to_f64() / r.to_f64();
+    };
+
+    let shift = r_magnitude.bits().saturating_sub(MAX_DIVISOR_BITS);
+    let fraction = match remainder.is_zero() {
+        true => 0.0,
+
+        false => {
+            remainder.shr_magnitude(shift).to_f64() / r_magnitude.shr_magnitude(shift).to_f64()
+        }
+    };
+
+    let quotient = quotient.to_f64() + fraction;
+
+    match l.is_negative() != r.is_negative() {
+        true => -quotient,
+        false => quotient,
+    }
+}
+
+fn float_arithmetic(op: OpTwo, l: f64, r: f64) -> f64 {
+    use OpTwo::*;
+
+    match op {
+        Plus => l + r,
+
+        Minus => l - r,
+
+        Star => l * r,
+
+        Slash => l / r,
+
+        // The remainder of floored division, and so with the sign of the divisor.
+        Percent => {
+            let remainder = l % r;
+
+            match remainder != 0.0 && (remainder < 0.0) != (r < 0.0) {
+                true => remainder + r,
+                false => remainder,
+            }
+        }
+
+        // Division rounded towards negative infinity, so `l == (l ~/ r) * r + l % r`.
+        TildeSlash => (l / r).floor(),
+
+        StarStar => l.powf(r),
+
+        _ => unreachable!("{op:?} is not an arithmetic operator"),
+    }
+}
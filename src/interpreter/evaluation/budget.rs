@@ -0,0 +1,132 @@
+use crate::interpreter::{
+    Base, TreeWalker,
+    ast::{
+        expression::{ExprB, Value},
+        identifier::{Address, Identifier},
+    },
+    environment::{Env, EnvHandle},
+    err::{Stumble, StumbleKind},
+};
+
+/// The number of steps between checks of the deadline and the cancellation flag, as either check is costly relative to a step.
+const CHECK_INTERVAL: u64 = 1024;
+
+impl TreeWalker {
+    /// Take a step of evaluation, terminating evaluation if a budget of the configuration is exhausted.
+    ///
+    /// A step is the interpretation of a statement, the evaluation of an expression, or a pass over the body of a loop.
+    /// The deadline and the cancellation flag are checked on the first step and periodically thereafter.
+    pub fn step(&self, base: &mut Base) -> Result<(), Stumble> {
+        base.steps += 1;
+
+        if let Some(max_steps) = self.config.max_steps
+            && max_steps < base.steps
+        {
+            return Err(self.stumble_token(StumbleKind::StepLimit { steps: max_steps }));
+        }
+
+        if base.steps % CHECK_INTERVAL == 1 {
+            if let Some(deadline) = self.config.deadline
+                && deadline <= std::time::Instant::now()
+            {
+                return Err(self.stumble_token(StumbleKind::DeadlineExceeded));
+            }
+
+            if let Some(cancel) = &self.config.cancel
+                && cancel.load(std::sync::atomic::Ordering::Relaxed)
+            {
+                return Err(self.stumble_token(StumbleKind::Cancelled));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check a call may be made, as a stack overflow if the calls in progress reach the maximum depth or use more stack than the configuration allows.
+    pub fn check_depth(&self, base: &mut Base) -> Result<(), Stumble> {
+        let marker = 0_u8;
+        let address = std::hint::black_box(&marker) as *const u8 as usize;
+
+        if base.frames.is_empty() {
+            base.stack_base = address;
+        }
+
+        let depth = base.frames.len();
+        let used = base.stack_base.abs_diff(address);
+
+        match self.config.max_call_depth <= depth || self.config.max_stack < used {
+            true => Err(self.stumble_token(StumbleKind::StackOverflow { depth })),
+
+            false => Ok(()),
+        }
+    }
+
+    /// Count `bytes` as allocated, terminating evaluation if the memory quota of the configuration is exceeded.
+    pub fn allocate(&self, bytes: usize, base: &mut Base) -> Result<(), Stumble> {
+        self.reserve(bytes, base)
+            .map_err(|kind| self.stumble_token(kind))?;
+
+        base.allocated = base.allocated.saturating_add(bytes);
+        Ok(())
+    }
+
+    /// Check `bytes` may be allocated within the memory quota of the configuration, without counting `bytes` as allocated.
+    ///
+    /// If the bytes counted as allocated would exceed the quota, the bytes held by live values and environments are measured and counted in their place.
+    /// A native may so check the size of a value before building the value, which is counted as allocated once returned.
+    pub fn reserve(&self, bytes: usize, base: &mut Base) -> Result<(), StumbleKind> {
+        let Some(limit) = self.config.max_memory else {
+            return Ok(());
+        };
+
+        if limit < base.allocated.saturating_add(bytes) {
+            base.allocated = base.heap.live_bytes(&self.interpret_env);
+        }
+
+        match limit < base.allocated.saturating_add(bytes) {
+            true => Err(StumbleKind::OutOfMemory { limit }),
+
+            false => Ok(()),
+        }
+    }
+
+    /// Count the bytes held by `value` as allocated, and return `value`.
+    ///
+    /// A closure, list, or map is tracked by the heap.
+    pub fn allocated(&self, value: ExprB, base: &mut Base) -> Result<ExprB, Stumble> {
+        self.allocate(value.size(), base)?;
+        base.heap.track_value(&value);
+        Ok(value)
+    }
+
+    /// An environment enclosed by `env`, counted as allocated and tracked by the heap.
+    pub fn narrow(&self, env: &EnvHandle, base: &mut Base) -> Result<EnvHandle, Stumble> {
+        self.allocate(std::mem::size_of::<Env>(), base)?;
+
+        let narrow_env = Env::narrow(env.clone());
+        base.heap.track_env(&narrow_env);
+        Ok(narrow_env)
+    }
+
+    /// Bind `id` to `value` in `env`, with the slot of the binding counted as allocated.
+    pub fn bind(
+        &self,
+        env: &EnvHandle,
+        id: &Identifier,
+        value: ExprB,
+        base: &mut Base,
+    ) -> Result<(), Stumble> {
+        self.allocate(std::mem::size_of::<Option<Value>>(), base)?;
+
+        match id.address() {
+            Some(Address { offset: 0, slot }) => {
+                env.borrow_mut().define(slot, value);
+            }
+
+            // Declarations are resolved to a slot of the environment declared in while parsing.
+            _ => panic!("! Binding of an unresolved identifier"),
+        }
+
+        Ok(())
+    }
+}
@@ -1,14 +1,23 @@
+use std::cmp::Ordering;
+
 use crate::interpreter::{
-    Base, TreeWalker,
+    Base, Control, TreeWalker,
     ast::{
-        expression::{Expr, ExprB, OpOne, OpTwo},
-        identifier::Identifier,
-        statement::Statement,
+        expression::{Expr, ExprB, Map, MapKey, Number, OpOne, OpTwo},
+        identifier::{Id, Identifier},
+        statement::Statements,
     },
-    environment::{Env, EnvHandle},
-    err::{Stumble, StumbleKind},
+    environment::EnvHandle,
+    err::{Frame, Stumble, StumbleKind},
+    location::Location,
+    natives,
 };
 
+use super::arithmetic::{arithmetic, negate};
+
+/// The greatest integer `n` such that each integer with magnitude at most `n` is exactly representable as an f64.
+pub const MAX_SAFE_INTEGRAL: i64 = (1 << 53) - 1;
+
 impl TreeWalker {
     pub fn eval_boolean(
         &self,
@@ -23,24 +32,90 @@ impl TreeWalker {
         }
     }
 
-    pub fn eval_numeric(
-        &self,
-        expr: &Expr,
-        env: &EnvHandle,
-        base: &mut Base,
-    ) -> Result<f64, Stumble> {
-        match self.eval(expr, env, base)? {
-            ExprB::Numeric { n } => Ok(n),
+    /// `value` as a number, or none if `value` is not a number.
+    ///
+    /// With string coercion, strings are converted to floats, if possible.
+    fn coerce(&self, value: &ExprB) -> Result<Option<Number>, Stumble> {
+        match value {
+            ExprB::String { s } if self.config.string_coercion => match s.parse::<f64>() {
+                Ok(result) => Ok(Some(Number::Float(result))),
 
-            ExprB::String { s } => {
-                if let Ok(result) = s.parse::<f64>() {
-                    Ok(result)
-                } else {
-                    panic!("Failed to convert string to numeric")
-                }
+                Err(_) => Err(self.stumble_token(StumbleKind::InvalidConversion)),
+            },
+
+            value => Ok(value.number()),
+        }
+    }
+
+    /// `value` as a number.
+    pub fn as_number(&self, value: ExprB) -> Result<Number, Stumble> {
+        match self.coerce(&value)? {
+            Some(n) => Ok(n),
+
+            None => Err(self.stumble_token(StumbleKind::ConflictingSubexpression)),
+        }
+    }
+
+    /// `value` as the number operand of the unary operator `op`.
+    fn unary_operand(&self, op: OpOne, value: ExprB) -> Result<Number, Stumble> {
+        match self.coerce(&value)? {
+            Some(n) => Ok(n),
+
+            None => Err(self.stumble_token(StumbleKind::TypeError {
+                op: op.to_string(),
+                left: None,
+                right: value.type_name(),
+            })),
+        }
+    }
+
+    /// `l` and `r` as the number operands of the binary operator `op`.
+    fn binary_operands(&self, op: OpTwo, l: ExprB, r: ExprB) -> Result<(Number, Number), Stumble> {
+        match (self.coerce(&l)?, self.coerce(&r)?) {
+            (Some(l), Some(r)) => Ok((l, r)),
+
+            _ => Err(self.stumble_token(StumbleKind::TypeError {
+                op: op.to_string(),
+                left: Some(l.type_name()),
+                right: r.type_name(),
+            })),
+        }
+    }
+
+    /// `value` as an i64, if `value` is an integer within the range of an i64 or an integral float whose magnitude is at most 2^53 - 1.
+    ///
+    /// Within this range each integer has a distinct representation as an f64, and so a float operand is exact.
+    pub fn as_integral(&self, value: ExprB) -> Result<i64, Stumble> {
+        let n = self.as_number(value)?;
+        self.integral(n)
+    }
+
+    /// `n` as an i64, as with [`as_integral`](Self::as_integral).
+    fn integral(&self, n: Number) -> Result<i64, Stumble> {
+        match n {
+            Number::Integer(i) => Ok(i),
+
+            Number::Float(n) if n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGRAL as f64 => {
+                Ok(n as i64)
+            }
+
+            Number::BigInteger(_) | Number::Float(_) => {
+                Err(self.stumble_token(StumbleKind::NotSafeIntegral))
             }
+        }
+    }
+
+    /// The integral operands of the binary operator `op`.
+    fn integral_operands(&self, op: OpTwo, l: ExprB, r: ExprB) -> Result<(i64, i64), Stumble> {
+        let (l, r) = self.binary_operands(op, l, r)?;
+        Ok((self.integral(l)?, self.integral(r)?))
+    }
 
-            e => panic!("{e:?}"),
+    fn shift_amount(&self, amount: i64) -> Result<u32, Stumble> {
+        match amount {
+            amount @ 0..64 => Ok(amount as u32),
+
+            _ => Err(self.stumble_token(StumbleKind::InvalidShift)),
         }
     }
 
@@ -51,7 +126,7 @@ impl TreeWalker {
         base: &mut Base,
     ) -> Result<String, Stumble> {
         match self.eval(expr, env, base)? {
-            ExprB::String { s } => Ok(s.to_owned()),
+            ExprB::String { s } => Ok(s.to_string()),
 
             _ => Err(self.stumble_token(StumbleKind::ConflictingSubexpression)),
         }
@@ -65,153 +140,623 @@ impl TreeWalker {
         }
     }
 
-    pub fn eval(&self, expr: &Expr, env: &EnvHandle, base: &mut Base) -> Result<ExprB, Stumble> {
-        let value = match expr {
-            Expr::Empty => ExprB::Nil,
+    /// Call `callee` with `args`, from `location`.
+    ///
+    /// A call of a function is a frame on the call stack of `base` for the duration of the call.
+    pub fn apply(
+        &self,
+        callee: ExprB,
+        args: Vec<ExprB>,
+        location: Location,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        match callee {
+            ExprB::Lambda { closure } => {
+                let params = &closure.params;
+
+                if params.len() != args.len() {
+                    return Err(self.stumble_token(StumbleKind::ArityMismatch {
+                        expected: params.len(),
+                        found: args.len(),
+                    }));
+                }
 
-            Expr::Basic(b_expr) => b_expr.clone(),
+                // TODO: Write the args to the same env as the body?
 
-            Expr::Identifier { id } => match env.borrow().get(id) {
-                None => {
-                    return Err(self.stumble_token(StumbleKind::InvalidIdentifier {
-                        id: id.name.clone(),
-                    }));
+                let args_env = self.narrow(&closure.env, base)?;
+                for (id, v) in params.iter().zip(args) {
+                    self.bind(&args_env, id, v, base)?;
                 }
 
-                Some(e) => return Ok(e.to_owned()),
-            },
+                if let Err(stumble) = self.check_depth(base) {
+                    return Err(stumble.traced(&base.frames));
+                }
 
-            Expr::Assignment {
-                id: name,
-                e: assignment,
-            } => {
-                let assignment = self.eval(assignment, env, base)?;
+                let body_env = self.narrow(&args_env, base)?;
 
-                let id = self.get_identifier(*name.clone())?;
+                base.frames.push(Frame {
+                    name: closure.name.clone(),
+                    location,
+                });
+                let value = self.call_body(&closure.body, &body_env, base);
+                let value = value.map_err(|stumble| stumble.traced(&base.frames));
+                base.frames.pop();
 
-                match env.borrow_mut().assign(id.name(), assignment.clone()) {
-                    Ok(_) => {}
+                value
+            }
 
-                    Err(e) => return Err(self.stumble_token(e)),
+            ExprB::Native { native } => {
+                if native.arity != args.len() {
+                    return Err(self.stumble_token(StumbleKind::ArityMismatch {
+                        expected: native.arity,
+                        found: args.len(),
+                    }));
+                }
+
+                let args = match native.receiver {
+                    Some(receiver) => std::iter::once(*receiver).chain(args).collect(),
+
+                    None => args,
                 };
 
-                assignment
+                let value =
+                    (native.f)(self, base, args).map_err(|kind| self.stumble_token(kind))?;
+
+                self.allocated(value, base)
+            }
+
+            _ => Err(self.stumble_token(StumbleKind::ExpectedLambda)),
+        }
+    }
+
+    /// The value returned by interpreting `body`, or nil if `body` completes without a return.
+    fn call_body(
+        &self,
+        body: &Statements,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        for statement in body {
+            if let (Control::Return, value) = self.interpret(statement, env, base)? {
+                return Ok(value);
+            }
+        }
+
+        Ok(ExprB::Nil)
+    }
+
+    /// The element of a list at `index`, or the value of a map at the key `index`.
+    ///
+    /// Absent keys of a map have the value nil.
+    pub fn get_index(&self, target: &ExprB, index: ExprB) -> Result<ExprB, Stumble> {
+        match target {
+            ExprB::List { l } => {
+                let list = l.borrow();
+                let index = self.list_index(&index, list.len())?;
+                Ok(list[index].clone())
             }
 
-            Expr::Grouping { e } => self.eval(e, env, base)?,
+            ExprB::Map { m } => match MapKey::from_expr(&index) {
+                Some(key) => Ok(m.borrow().get(&key).cloned().unwrap_or(ExprB::Nil)),
+
+                None => Err(self.stumble_token(StumbleKind::UnhashableKey)),
+            },
+
+            _ => Err(self.stumble_token(StumbleKind::NotIndexable)),
+        }
+    }
 
-            Expr::Unary { op, e } => {
-                use OpOne::*;
-                match op {
-                    Minus => ExprB::mk_numeric(-self.eval_numeric(e, env, base)?),
+    /// The property `name` of `target`.
+    ///
+    /// The properties of a map are the values of its string keys, and the properties of a string are its methods, bound to the string.
+    pub fn get_property(&self, target: ExprB, name: &Id) -> Result<ExprB, Stumble> {
+        let method = match &target {
+            ExprB::Map { m } => {
+                let key = MapKey::String { s: name.string() };
 
-                    Bang => ExprB::mk_bool(!(self.eval_boolean(e, env, base)?)),
+                if let Some(value) = m.borrow().get(&key) {
+                    return Ok(value.clone());
                 }
+
+                None
             }
 
-            Expr::Binary { op, a: l, b: r } => {
-                use OpTwo::*;
-                match op {
-                    Minus => ExprB::mk_numeric(
-                        self.eval_numeric(l, env, base)? - self.eval_numeric(r, env, base)?,
-                    ),
+            ExprB::String { .. } => natives::string_method(name),
 
-                    Slash => ExprB::mk_numeric(
-                        self.eval_numeric(l, env, base)? / self.eval_numeric(r, env, base)?,
-                    ),
+            _ => None,
+        };
+
+        match method {
+            Some(native) => Ok(ExprB::Native {
+                native: native.bind(target),
+            }),
+
+            None => Err(self.stumble_token(StumbleKind::UnknownProperty {
+                name: name.to_string(),
+            })),
+        }
+    }
 
-                    Star => ExprB::mk_numeric(
-                        self.eval_numeric(l, env, base)? * self.eval_numeric(r, env, base)?,
-                    ),
+    /// Set the element of a list at `index`, or the value of a map at the key `index`, to `value`.
+    ///
+    /// A key new to a map is counted as allocated.
+    pub fn set_index(
+        &self,
+        target: &ExprB,
+        index: ExprB,
+        value: ExprB,
+        base: &mut Base,
+    ) -> Result<(), Stumble> {
+        match target {
+            ExprB::List { l } => {
+                let mut list = l.borrow_mut();
+                let index = self.list_index(&index, list.len())?;
+                list[index] = value;
+                Ok(())
+            }
 
-                    Plus => match (self.eval(l, env, base)?, self.eval(r, env, base)?) {
-                        (ExprB::Numeric { n: l }, ExprB::Numeric { n: r }) => {
-                            ExprB::mk_numeric(l + r)
-                        }
+            ExprB::Map { m } => match MapKey::from_expr(&index) {
+                Some(key) => {
+                    // The map is not borrowed while allocating, so the map is measured if the quota would be exceeded.
+                    let previous = m.borrow_mut().insert(key, value);
 
-                        (ExprB::String { s: mut l }, ExprB::String { s: r }) => {
-                            l.push_str(r.as_str());
-                            ExprB::mk_string(l)
-                        }
+                    match previous {
+                        Some(_) => Ok(()),
 
-                        _ => {
-                            return Err(self.stumble_token(StumbleKind::ConflictingSubexpression));
-                        }
-                    },
+                        None => self.allocate(Map::ENTRY_SIZE, base),
+                    }
+                }
+
+                None => Err(self.stumble_token(StumbleKind::UnhashableKey)),
+            },
+
+            _ => Err(self.stumble_token(StumbleKind::NotIndexable)),
+        }
+    }
+
+    fn list_index(&self, index: &ExprB, length: usize) -> Result<usize, Stumble> {
+        let index = match index.number() {
+            Some(Number::Integer(i)) => usize::try_from(i).ok(),
+
+            Some(Number::Float(n)) if n.fract() == 0.0 && 0.0 <= n => Some(n as usize),
+
+            _ => None,
+        };
+
+        match index {
+            Some(index) if index < length => Ok(index),
+
+            Some(index) => Err(self.stumble_token(StumbleKind::IndexOutOfBounds { index, length })),
+
+            None => Err(self.stumble_token(StumbleKind::InvalidIndex)),
+        }
+    }
 
-                    Gt => ExprB::mk_bool(
-                        self.eval_numeric(l, env, base)? > self.eval_numeric(r, env, base)?,
-                    ),
+    /// The ordering of `l` and `r` as numbers, compared by `op`.
+    fn compare(&self, op: OpTwo, l: ExprB, r: ExprB) -> Result<Option<Ordering>, Stumble> {
+        let (l, r) = self.binary_operands(op, l, r)?;
+        Ok(l.compare(&r))
+    }
 
-                    Geq => ExprB::mk_bool(
-                        self.eval_numeric(l, env, base)? >= self.eval_numeric(r, env, base)?,
-                    ),
+    /// The result of applying `op` to the values `l` and `r`.
+    pub fn binary(&self, op: OpTwo, l: ExprB, r: ExprB) -> Result<ExprB, Stumble> {
+        use OpTwo::*;
 
-                    Lt => ExprB::mk_bool(
-                        self.eval_numeric(l, env, base)? < self.eval_numeric(r, env, base)?,
-                    ),
+        let value = match op {
+            Plus | Minus | Star | Slash | Percent | TildeSlash | StarStar => match (op, l, r) {
+                (Plus, ExprB::String { s: l }, ExprB::String { s: r }) => {
+                    ExprB::mk_string([l, r].concat())
+                }
 
-                    Leq => ExprB::mk_bool(
-                        self.eval_numeric(l, env, base)? <= self.eval_numeric(r, env, base)?,
-                    ),
+                (Plus, l, r) if l.number().is_none() || r.number().is_none() => {
+                    return Err(self.stumble_token(StumbleKind::TypeError {
+                        op: op.to_string(),
+                        left: Some(l.type_name()),
+                        right: r.type_name(),
+                    }));
+                }
 
-                    Eq => ExprB::mk_bool(self.eval(l, env, base)? == self.eval(r, env, base)?),
+                (op, l, r) => {
+                    let (l, r) = self.binary_operands(op, l, r)?;
+                    match arithmetic(op, l, r) {
+                        Ok(n) => n.to_expr(),
 
-                    Neq => ExprB::mk_bool(self.eval(l, env, base)? != self.eval(r, env, base)?),
+                        Err(e) => return Err(self.stumble_token(e)),
+                    }
                 }
+            },
+
+            Ampersand => {
+                let (l, r) = self.integral_operands(op, l, r)?;
+                ExprB::mk_integer(l & r)
             }
 
-            Expr::Or { a, b } => {
-                let a_value = self.eval(a, env, base)?;
+            Pipe => {
+                let (l, r) = self.integral_operands(op, l, r)?;
+                ExprB::mk_integer(l | r)
+            }
+
+            Caret => {
+                let (l, r) = self.integral_operands(op, l, r)?;
+                ExprB::mk_integer(l ^ r)
+            }
+
+            LessLess => {
+                let (l, r) = self.integral_operands(op, l, r)?;
+                let r = self.shift_amount(r)?;
+                match i64::try_from((l as i128) << r) {
+                    Ok(shifted) => ExprB::mk_integer(shifted),
 
-                if a_value.is_truthy() {
-                    a_value
-                } else {
-                    self.eval(b, env, base)?
+                    Err(_) => return Err(self.stumble_token(StumbleKind::BitwiseOverflow)),
                 }
             }
 
-            Expr::And { a, b } => {
-                let a_value = self.eval(a, env, base)?;
+            GreaterGreater => {
+                let (l, r) = self.integral_operands(op, l, r)?;
+                let r = self.shift_amount(r)?;
+                ExprB::mk_integer(l >> r)
+            }
+
+            Gt => ExprB::mk_bool(matches!(self.compare(op, l, r)?, Some(Ordering::Greater))),
+
+            Geq => ExprB::mk_bool(matches!(
+                self.compare(op, l, r)?,
+                Some(Ordering::Greater | Ordering::Equal)
+            )),
+
+            Lt => ExprB::mk_bool(matches!(self.compare(op, l, r)?, Some(Ordering::Less))),
 
-                if a_value.is_falsey() {
-                    a_value
-                } else {
-                    self.eval(b, env, base)?
+            Leq => ExprB::mk_bool(matches!(
+                self.compare(op, l, r)?,
+                Some(Ordering::Less | Ordering::Equal)
+            )),
+
+            Eq => ExprB::mk_bool(l == r),
+
+            Neq => ExprB::mk_bool(l != r),
+        };
+
+        Ok(value)
+    }
+
+    /// The value of `expr`.
+    ///
+    /// As `eval` recurses through nested expressions and calls, each variant other than the simplest is evaluated by a distinct method, which keeps the stack frame of `eval` itself small.
+    pub fn eval(&self, expr: &Expr, env: &EnvHandle, base: &mut Base) -> Result<ExprB, Stumble> {
+        self.step(base)?;
+
+        match expr {
+            Expr::Empty => Ok(ExprB::Nil),
+
+            Expr::Basic(b_expr) => Ok(b_expr.clone()),
+
+            Expr::Identifier { id } => match env.borrow().get(id) {
+                None => Err(self.stumble_token(StumbleKind::InvalidIdentifier {
+                    id: id.name.to_string(),
+                })),
+
+                Some(e) => Ok(e.to_owned()),
+            },
+
+            Expr::Assignment { id, e } => self.eval_assignment(id, e, env, base),
+
+            Expr::Grouping { e } => self.eval(e, env, base),
+
+            Expr::Unary { op, e } => self.eval_unary(*op, e, env, base),
+
+            Expr::Binary { op, a, b } => self.eval_binary(*op, a, b, env, base),
+
+            Expr::Update {
+                op,
+                target,
+                e,
+                postfix,
+            } => self.eval_update(*op, target, e, *postfix, env, base),
+
+            Expr::Ternary {
+                condition,
+                case_if,
+                case_else,
+            } => self.eval_ternary(condition, case_if, case_else, env, base),
+
+            Expr::Or { a, b } => self.eval_logical(true, a, b, env, base),
+
+            Expr::And { a, b } => self.eval_logical(false, a, b, env, base),
+
+            Expr::Call {
+                caller,
+                args,
+                location,
+            } => self.eval_call(caller, args, *location, env, base),
+
+            Expr::List { elements } => self.eval_list(elements, env, base),
+
+            Expr::Map { entries } => self.eval_map(entries, env, base),
+
+            Expr::Index { target, index } => self.eval_index(target, index, env, base),
+
+            Expr::Get { target, name } => self.eval_get(target, name, env, base),
+
+            Expr::Interpolation { parts } => self.eval_interpolation(parts, env, base),
+
+            Expr::Located { location, e } => self
+                .eval(e, env, base)
+                .map_err(|stumble| stumble.locate(*location)),
+
+            Expr::Range {
+                start,
+                end,
+                inclusive,
+            } => self.eval_range(start, end, *inclusive, env, base),
+        }
+    }
+
+    fn eval_assignment(
+        &self,
+        target: &Expr,
+        e: &Expr,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        match target {
+            Expr::Index { target, index } => {
+                let target = self.eval(target, env, base)?;
+                let index = self.eval(index, env, base)?;
+                let assignment = self.eval(e, env, base)?;
+
+                self.set_index(&target, index, assignment.clone(), base)?;
+
+                Ok(assignment)
+            }
+
+            _ => {
+                let assignment = self.eval(e, env, base)?;
+
+                let id = self.get_identifier(target.clone())?;
+
+                match env.borrow_mut().assign(&id, assignment.clone()) {
+                    Ok(_) => Ok(assignment),
+
+                    Err(e) => Err(self.stumble_token(e)),
                 }
             }
+        }
+    }
+
+    fn eval_unary(
+        &self,
+        op: OpOne,
+        e: &Expr,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        let value = self.eval(e, env, base)?;
+        self.unary_value(op, value)
+    }
+
+    /// The result of applying `op` to the value `value`.
+    pub fn unary_value(&self, op: OpOne, value: ExprB) -> Result<ExprB, Stumble> {
+        use OpOne::*;
+
+        match op {
+            Minus => Ok(negate(self.unary_operand(op, value)?).to_expr()),
+
+            Bang => match value {
+                ExprB::Boolean { b } => Ok(ExprB::mk_bool(!b)),
+
+                _ => Err(self.stumble_token(StumbleKind::ConflictingSubexpression)),
+            },
+
+            Tilde => {
+                let n = self.unary_operand(op, value)?;
+                Ok(ExprB::mk_integer(!self.integral(n)?))
+            }
+        }
+    }
 
-            Expr::Call { caller, args } => {
-                match self.eval(caller, env, base)? {
-                    ExprB::Lambda {
-                        env: lenv,
-                        params,
-                        body,
-                    } => {
-                        // TODO: Write the args to the same env as the body?
-
-                        let args_env = Env::narrow(lenv);
-                        for (id, v) in params.iter().zip(args.iter()) {
-                            let bv = self.eval(v, env, base)?;
-                            args_env.borrow_mut().insert(id.name(), bv);
-                        }
-
-                        let body_env = Env::narrow(args_env);
-                        for statement in &body {
-                            let expr = self.interpret(statement, &body_env, base)?;
-                            if let Statement::Return { .. } = statement {
-                                return Ok(expr.1);
-                            }
-                        }
-                    }
+    fn eval_binary(
+        &self,
+        op: OpTwo,
+        l: &Expr,
+        r: &Expr,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        let l = self.eval(l, env, base)?;
+        let r = self.eval(r, env, base)?;
+
+        let value = self.binary(op, l, r)?;
+        self.allocated(value, base)
+    }
 
-                    _ => return Err(self.stumble_token(StumbleKind::ExpectedLambda)),
+    fn eval_update(
+        &self,
+        op: OpTwo,
+        target: &Expr,
+        e: &Expr,
+        postfix: bool,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        let (current, updated) = match target {
+            Expr::Identifier { id } => {
+                let current = self.eval(target, env, base)?;
+                let e = self.eval(e, env, base)?;
+                let updated = self.binary(op, current.clone(), e)?;
+                let updated = self.allocated(updated, base)?;
+
+                if let Err(e) = env.borrow_mut().assign(id, updated.clone()) {
+                    return Err(self.stumble_token(e));
                 }
 
-                ExprB::Nil
+                (current, updated)
             }
+
+            Expr::Index { target, index } => {
+                let target = self.eval(target, env, base)?;
+                let index = self.eval(index, env, base)?;
+
+                let current = self.get_index(&target, index.clone())?;
+                let e = self.eval(e, env, base)?;
+                let updated = self.binary(op, current.clone(), e)?;
+                let updated = self.allocated(updated, base)?;
+
+                self.set_index(&target, index, updated.clone(), base)?;
+
+                (current, updated)
+            }
+
+            _ => return Err(self.stumble_token(StumbleKind::InvalidAssignTo)),
         };
 
-        Ok(value)
+        match postfix {
+            true => Ok(current),
+            false => Ok(updated),
+        }
+    }
+
+    fn eval_ternary(
+        &self,
+        condition: &Expr,
+        case_if: &Expr,
+        case_else: &Expr,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        match self.eval(condition, env, base)?.is_truthy() {
+            true => self.eval(case_if, env, base),
+            false => self.eval(case_else, env, base),
+        }
+    }
+
+    /// An `or` if `or`, and otherwise an `and`, which evaluates `b` only if the truthiness of `a` does not decide the value.
+    fn eval_logical(
+        &self,
+        or: bool,
+        a: &Expr,
+        b: &Expr,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        let a_value = self.eval(a, env, base)?;
+
+        match a_value.is_truthy() == or {
+            true => Ok(a_value),
+            false => self.eval(b, env, base),
+        }
+    }
+
+    fn eval_call(
+        &self,
+        caller: &Expr,
+        args: &[Expr],
+        location: Location,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        let callee = self.eval(caller, env, base)?;
+
+        let mut arguments = Vec::with_capacity(args.len());
+        for arg in args {
+            arguments.push(self.eval(arg, env, base)?);
+        }
+
+        self.apply(callee, arguments, location, base)
+    }
+
+    fn eval_list(
+        &self,
+        elements: &[Expr],
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        let mut list = Vec::with_capacity(elements.len());
+        for element in elements {
+            list.push(self.eval(element, env, base)?);
+        }
+
+        self.allocated(ExprB::mk_list(list), base)
+    }
+
+    fn eval_map(
+        &self,
+        entries: &[(Expr, Expr)],
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        let mut map = Map::default();
+        for (key, value) in entries {
+            let key = self.eval(key, env, base)?;
+            let key = match MapKey::from_expr(&key) {
+                Some(key) => key,
+
+                None => return Err(self.stumble_token(StumbleKind::UnhashableKey)),
+            };
+
+            map.insert(key, self.eval(value, env, base)?);
+        }
+
+        self.allocated(ExprB::mk_map(map), base)
+    }
+
+    fn eval_index(
+        &self,
+        target: &Expr,
+        index: &Expr,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        let target = self.eval(target, env, base)?;
+        let index = self.eval(index, env, base)?;
+
+        self.get_index(&target, index)
+    }
+
+    fn eval_get(
+        &self,
+        target: &Expr,
+        name: &Id,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        let target = self.eval(target, env, base)?;
+
+        self.get_property(target, name)
+    }
+
+    fn eval_interpolation(
+        &self,
+        parts: &[Expr],
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        let mut interpolation = String::default();
+        for part in parts {
+            let value = self.eval(part, env, base)?;
+            interpolation.push_str(&value.to_string());
+        }
+
+        self.allocated(ExprB::mk_string(interpolation), base)
+    }
+
+    fn eval_range(
+        &self,
+        start: &Expr,
+        end: &Expr,
+        inclusive: bool,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<ExprB, Stumble> {
+        let start = self.eval(start, env, base)?;
+        let start = self.as_integral(start)?;
+
+        let end = self.eval(end, env, base)?;
+        let end = self.as_integral(end)?;
+
+        Ok(ExprB::Range {
+            start,
+            end,
+            inclusive,
+        })
     }
 }
@@ -0,0 +1,132 @@
+use crate::interpreter::{
+    Base, TreeWalker,
+    ast::expression::{ExprB, ListHandle, MapKey},
+    err::{Stumble, StumbleKind},
+    location::Location,
+};
+
+/// The state of iteration over some iterable value.
+pub enum Iteration {
+    /// The elements of a list, read as iteration proceeds so appended elements are included.
+    List { l: ListHandle, index: usize },
+
+    /// Values fixed when iteration began, such as the keys of a map or the characters of a string.
+    Values { values: std::vec::IntoIter<ExprB> },
+
+    /// Integers from `next` to `end`, in steps of one.
+    Range {
+        next: i64,
+        end: i64,
+        inclusive: bool,
+    },
+
+    /// The results of repeatedly calling `f` with no arguments, until `f` returns nil.
+    Callable { f: ExprB },
+}
+
+impl TreeWalker {
+    /// The iteration over `iterable`.
+    ///
+    /// - Lists iterate over their elements.
+    /// - Maps iterate over their keys, unless the map has a callable `next` value, in which case the map is iterated as a callable.
+    /// - Strings iterate over their characters.
+    /// - Ranges iterate over the integers in the range.
+    /// - Callables are called until they return nil.
+    pub fn iteration(&self, iterable: ExprB) -> Result<Iteration, Stumble> {
+        let next_key = MapKey::String { s: "next".into() };
+
+        let iteration = match iterable {
+            ExprB::List { l } => Iteration::List { l, index: 0 },
+
+            ExprB::Map { m } => match m.borrow().get(&next_key) {
+                Some(f @ (ExprB::Lambda { .. } | ExprB::Native { .. })) => {
+                    Iteration::Callable { f: f.clone() }
+                }
+
+                _ => Iteration::Values {
+                    values: m
+                        .borrow()
+                        .keys()
+                        .map(MapKey::to_expr)
+                        .collect::<Vec<_>>()
+                        .into_iter(),
+                },
+            },
+
+            ExprB::String { s } => Iteration::Values {
+                values: s
+                    .chars()
+                    .map(|c| ExprB::mk_string(c.to_string()))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            },
+
+            ExprB::Range {
+                start,
+                end,
+                inclusive,
+            } => Iteration::Range {
+                next: start,
+                end,
+                inclusive,
+            },
+
+            f @ (ExprB::Lambda { .. } | ExprB::Native { .. }) => Iteration::Callable { f },
+
+            _ => return Err(self.stumble_token(StumbleKind::NotIterable)),
+        };
+
+        Ok(iteration)
+    }
+
+    /// The next value of `iteration`, if any.
+    pub fn iterate(
+        &self,
+        iteration: &mut Iteration,
+        location: Location,
+        base: &mut Base,
+    ) -> Result<Option<ExprB>, Stumble> {
+        match iteration {
+            Iteration::List { l, index } => {
+                let element = l.borrow().get(*index).cloned();
+                *index += 1;
+                Ok(element)
+            }
+
+            Iteration::Values { values } => Ok(values.next()),
+
+            Iteration::Range {
+                next,
+                end,
+                inclusive,
+            } => {
+                let within = match inclusive {
+                    true => *next <= *end,
+                    false => *next < *end,
+                };
+
+                if within {
+                    let value = ExprB::mk_integer(*next);
+
+                    // The only range which ends past the greatest integer is inclusive of it, and ends after it.
+                    match next.checked_add(1) {
+                        Some(following) => *next = following,
+                        None => *inclusive = false,
+                    }
+
+                    Ok(Some(value))
+                } else {
+                    Ok(None)
+                }
+            }
+
+            Iteration::Callable { f } => {
+                match self.apply(f.clone(), Vec::default(), location, base)? {
+                    ExprB::Nil => Ok(None),
+
+                    value => Ok(Some(value)),
+                }
+            }
+        }
+    }
+}
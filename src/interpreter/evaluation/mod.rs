@@ -1 +1,4 @@
+pub mod arithmetic;
+pub mod budget;
 pub mod evaluate;
+pub mod iterate;
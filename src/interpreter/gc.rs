@@ -0,0 +1,469 @@
+//! A collector of reference cycles among environments, closures, lists, and maps.
+//!
+//! Environments, closures, lists, and maps are reference counted, and so freed when the last reference is dropped.
+//! Though, a closure stored in the environment it closes over, or a list which contains itself, forms a cycle which is never freed.
+//!
+//! The heap tracks each environment, closure, list, and map created by evaluation, and a collection finds those objects which are referenced only from within tracked objects and are not reachable from an object referenced from elsewhere.
+//! As the count of references from elsewhere includes references held by the host, such as the environment of a call in progress, there is no need to enumerate roots.
+//! The contents of each such object are then cleared, which breaks each cycle and frees the objects.
+//!
+//! After a collection, the tracked objects are those which are live, and so the bytes held by live objects may be measured.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::{Rc, Weak},
+};
+
+use crate::interpreter::{
+    ast::expression::{Closure, ExprB, List, ListHandle, Map, MapHandle, Value},
+    environment::{Env, EnvHandle},
+};
+
+/// The least number of tracked objects at which a collection is made.
+const MIN_THRESHOLD: usize = 1024;
+
+/// A tracked object.
+#[derive(Clone)]
+enum Object {
+    Closure(Rc<Closure>),
+    Env(EnvHandle),
+    List(ListHandle),
+    Map(MapHandle),
+}
+
+/// A weak reference to a tracked object.
+enum Tracked {
+    Closure(Weak<Closure>),
+    Env(Weak<RefCell<Env>>),
+    List(Weak<RefCell<List>>),
+    Map(Weak<RefCell<Map>>),
+}
+
+/// Statistics of a heap.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HeapStats {
+    /// The number of objects tracked, some of which may have been freed since the last collection.
+    pub tracked: usize,
+
+    /// The number of collections made.
+    pub collections: usize,
+
+    /// The number of objects freed by collections.
+    pub collected: usize,
+}
+
+/// The objects created by evaluation, tracked to collect cycles.
+pub struct Heap {
+    tracked: Vec<Tracked>,
+
+    /// The number of tracked objects at which a collection is made.
+    threshold: usize,
+
+    collections: usize,
+    collected: usize,
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Heap {
+            tracked: Vec::default(),
+            threshold: MIN_THRESHOLD,
+            collections: 0,
+            collected: 0,
+        }
+    }
+}
+
+impl Object {
+    /// The address of the object, which identifies the object while a reference to the object is held.
+    fn address(&self) -> usize {
+        match self {
+            Object::Closure(c) => Rc::as_ptr(c) as usize,
+            Object::Env(e) => Rc::as_ptr(e) as usize,
+            Object::List(l) => Rc::as_ptr(l) as usize,
+            Object::Map(m) => Rc::as_ptr(m) as usize,
+        }
+    }
+
+    fn strong_count(&self) -> usize {
+        match self {
+            Object::Closure(c) => Rc::strong_count(c),
+            Object::Env(e) => Rc::strong_count(e),
+            Object::List(l) => Rc::strong_count(l),
+            Object::Map(m) => Rc::strong_count(m),
+        }
+    }
+
+    /// Call `f` with each object referenced directly by the object.
+    ///
+    /// An object borrowed mutably is skipped, and so found to be referenced from elsewhere, as are the objects the object references.
+    fn for_each_reference(&self, f: &mut impl FnMut(Object)) {
+        match self {
+            Object::Closure(c) => f(Object::Env(c.env.clone())),
+
+            Object::Env(e) => {
+                if let Ok(env) = e.try_borrow() {
+                    if let Some(enclosing) = env.enclosing() {
+                        f(Object::Env(enclosing));
+                    }
+
+                    env.values().for_each(|value| value_references(value, f));
+                }
+            }
+
+            Object::List(l) => {
+                if let Ok(list) = l.try_borrow() {
+                    list.iter().for_each(|value| references(value, f));
+                }
+            }
+
+            Object::Map(m) => {
+                if let Ok(map) = m.try_borrow() {
+                    map.values().for_each(|value| references(value, f));
+                }
+            }
+        }
+    }
+
+    /// The number of bytes held by the object, with the strings and big integers held by the object other than strings in `counted`.
+    ///
+    /// Strings are shared, so each string counted is added to `counted`.
+    /// An object borrowed mutably is counted without its contents.
+    fn size(&self, counted: &mut HashSet<usize>) -> usize {
+        match self {
+            Object::Closure(_) => std::mem::size_of::<Closure>(),
+
+            Object::Env(e) => match e.try_borrow() {
+                Ok(env) => env.size() + env.values().map(|v| value_held(v, counted)).sum::<usize>(),
+
+                Err(_) => std::mem::size_of::<Env>(),
+            },
+
+            Object::List(l) => match l.try_borrow() {
+                Ok(list) => {
+                    let elements = list.iter().map(|v| held(v, counted)).sum::<usize>();
+                    list.len() * std::mem::size_of::<ExprB>() + elements
+                }
+
+                Err(_) => 0,
+            },
+
+            Object::Map(m) => match m.try_borrow() {
+                Ok(map) => {
+                    let entries = map
+                        .iter()
+                        .map(|(k, v)| held(&k.to_expr(), counted) + held(v, counted))
+                        .sum::<usize>();
+                    map.len() * Map::ENTRY_SIZE + entries
+                }
+
+                Err(_) => 0,
+            },
+        }
+    }
+
+    /// Drop the contents of the object, breaking any cycle through the object.
+    ///
+    /// A closure is immutable, though any cycle through a closure is broken as the environment of the closure is cleared.
+    fn clear(&self) {
+        match self {
+            Object::Closure(_) => {}
+
+            Object::Env(e) => {
+                if let Ok(mut env) = e.try_borrow_mut() {
+                    env.clear();
+                }
+            }
+
+            Object::List(l) => {
+                if let Ok(mut list) = l.try_borrow_mut() {
+                    list.clear();
+                }
+            }
+
+            Object::Map(m) => {
+                if let Ok(mut map) = m.try_borrow_mut() {
+                    map.clear();
+                }
+            }
+        }
+    }
+}
+
+/// Call `f` with each object referenced directly by `value`.
+fn references(value: &ExprB, f: &mut impl FnMut(Object)) {
+    match value {
+        ExprB::Lambda { closure } => f(Object::Closure(closure.clone())),
+
+        ExprB::List { l } => f(Object::List(l.clone())),
+
+        ExprB::Map { m } => f(Object::Map(m.clone())),
+
+        ExprB::Native { native } => {
+            if let Some(receiver) = &native.receiver {
+                references(receiver, f)
+            }
+        }
+
+        _ => {}
+    }
+}
+
+/// The number of bytes of the string or big integer `value`, if `value` is not a string in `counted`.
+fn held(value: &ExprB, counted: &mut HashSet<usize>) -> usize {
+    match value {
+        ExprB::String { s } if counted.insert(Rc::as_ptr(s) as *const u8 as usize) => s.len(),
+
+        ExprB::BigInteger { i } => i.size(),
+
+        ExprB::Native { native } => match &native.receiver {
+            Some(receiver) => held(receiver, counted),
+
+            None => 0,
+        },
+
+        _ => 0,
+    }
+}
+
+/// Call `f` with each object referenced directly by the value of a variable.
+fn value_references(value: &Value, f: &mut impl FnMut(Object)) {
+    match value {
+        Value::Lambda(closure) => f(Object::Closure(closure.clone())),
+
+        Value::List(l) => f(Object::List(l.clone())),
+
+        Value::Map(m) => f(Object::Map(m.clone())),
+
+        Value::Native(native) => {
+            if let Some(receiver) = &native.receiver {
+                references(receiver, f)
+            }
+        }
+
+        _ => {}
+    }
+}
+
+/// The number of bytes of the string or big integer value of a variable, if not a string in `counted`.
+fn value_held(value: &Value, counted: &mut HashSet<usize>) -> usize {
+    match value {
+        Value::String(s) if counted.insert(Rc::as_ptr(s) as *const u8 as usize) => s.len(),
+
+        Value::BigInteger(i) => i.size(),
+
+        Value::Native(native) => match &native.receiver {
+            Some(receiver) => held(receiver, counted),
+
+            None => 0,
+        },
+
+        _ => 0,
+    }
+}
+
+impl Tracked {
+    fn upgrade(&self) -> Option<Object> {
+        match self {
+            Tracked::Closure(c) => c.upgrade().map(Object::Closure),
+            Tracked::Env(e) => e.upgrade().map(Object::Env),
+            Tracked::List(l) => l.upgrade().map(Object::List),
+            Tracked::Map(m) => m.upgrade().map(Object::Map),
+        }
+    }
+}
+
+impl Heap {
+    /// Track `env`, collecting if the number of tracked objects has reached the threshold.
+    pub fn track_env(&mut self, env: &EnvHandle) {
+        self.track(Tracked::Env(Rc::downgrade(env)));
+    }
+
+    /// Track the closure, list, or map `value`, if `value` is a closure, list, or map, collecting if the number of tracked objects has reached the threshold.
+    pub fn track_value(&mut self, value: &ExprB) {
+        match value {
+            ExprB::Lambda { closure } => self.track(Tracked::Closure(Rc::downgrade(closure))),
+
+            ExprB::List { l } => self.track(Tracked::List(Rc::downgrade(l))),
+
+            ExprB::Map { m } => self.track(Tracked::Map(Rc::downgrade(m))),
+
+            _ => {}
+        }
+    }
+
+    fn track(&mut self, tracked: Tracked) {
+        self.tracked.push(tracked);
+
+        if self.threshold <= self.tracked.len() {
+            self.collect();
+            self.threshold = MIN_THRESHOLD.max(2 * self.tracked.len());
+        }
+    }
+
+    /// Free each tracked object which is unreachable other than through a cycle, returning the number of objects freed.
+    pub fn collect(&mut self) -> usize {
+        // Upgrade each tracked object, forgetting those already freed and any object tracked more than once.
+        let mut objects: Vec<Object> = Vec::with_capacity(self.tracked.len());
+        let mut indices: HashMap<usize, usize> = HashMap::with_capacity(self.tracked.len());
+
+        self.tracked.retain(|tracked| match tracked.upgrade() {
+            Some(object) if !indices.contains_key(&object.address()) => {
+                indices.insert(object.address(), objects.len());
+                objects.push(object);
+                true
+            }
+
+            _ => false,
+        });
+
+        // The references to each object from tracked objects.
+        let mut internal = vec![0; objects.len()];
+        for object in &objects {
+            object.for_each_reference(&mut |referenced| {
+                if let Some(&index) = indices.get(&referenced.address()) {
+                    internal[index] += 1;
+                }
+            });
+        }
+
+        // An object with a reference from elsewhere is live, as is any object reachable from a live object.
+        // The reference held by `objects` is not counted.
+        let mut live = vec![false; objects.len()];
+        let mut pending: Vec<usize> = (0..objects.len())
+            .filter(|&index| internal[index] < objects[index].strong_count() - 1)
+            .collect();
+
+        while let Some(index) = pending.pop() {
+            if live[index] {
+                continue;
+            }
+            live[index] = true;
+
+            objects[index].for_each_reference(&mut |referenced| {
+                if let Some(&index) = indices.get(&referenced.address())
+                    && !live[index]
+                {
+                    pending.push(index);
+                }
+            });
+        }
+
+        let mut collected = 0;
+        for (object, live) in objects.iter().zip(&live) {
+            if !live {
+                object.clear();
+                collected += 1;
+            }
+        }
+
+        drop(objects);
+        self.tracked.retain(|tracked| tracked.upgrade().is_some());
+
+        self.collections += 1;
+        self.collected += collected;
+
+        collected
+    }
+
+    /// The number of bytes held by live objects, after a collection, with those held by `root` and the environments enclosing `root`.
+    pub fn live_bytes(&mut self, root: &EnvHandle) -> usize {
+        self.collect();
+
+        let mut objects: Vec<Object> = self.tracked.iter().filter_map(Tracked::upgrade).collect();
+
+        let mut env = Some(root.clone());
+        while let Some(e) = env {
+            env = e.borrow().enclosing();
+            objects.push(Object::Env(e));
+        }
+
+        let mut addresses = HashSet::with_capacity(objects.len());
+        let mut counted = HashSet::default();
+
+        objects
+            .iter()
+            .filter(|object| addresses.insert(object.address()))
+            .map(|object| object.size(&mut counted))
+            .sum()
+    }
+
+    pub fn stats(&self) -> HeapStats {
+        HeapStats {
+            tracked: self.tracked.len(),
+            collections: self.collections,
+            collected: self.collected,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::interpreter::interner::Interner;
+
+    /// An environment holding a closure over the environment, and so a cycle.
+    fn closure_cycle(heap: &mut Heap) -> EnvHandle {
+        let env = Env::narrow(Env::fresh_global_handle());
+        heap.track_env(&env);
+
+        let f = Interner::default().symbol("f");
+        let lambda = ExprB::mk_lambda(f.string(), env.clone(), Rc::default(), Rc::default());
+        heap.track_value(&lambda);
+        env.borrow_mut().insert(&f, lambda);
+
+        env
+    }
+
+    #[test]
+    fn collects_cycles() {
+        let mut heap = Heap::default();
+
+        let env = closure_cycle(&mut heap);
+        let weak = Rc::downgrade(&env);
+        drop(env);
+
+        assert!(weak.upgrade().is_some());
+        assert_eq!(heap.collect(), 2);
+        assert!(weak.upgrade().is_none());
+
+        let list = ExprB::mk_list(Vec::default());
+        let ExprB::List { l } = &list else {
+            unreachable!()
+        };
+        l.borrow_mut().push(list.clone());
+        heap.track_value(&list);
+
+        let weak = Rc::downgrade(l);
+        drop(list);
+
+        assert_eq!(heap.collect(), 1);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn keeps_referenced() {
+        let mut heap = Heap::default();
+
+        let env = closure_cycle(&mut heap);
+
+        assert_eq!(heap.collect(), 0);
+        assert!(env.borrow().values().next().is_some());
+
+        // An environment enclosed by a referenced environment is live, though referenced only from a tracked environment.
+        let inner = Env::narrow(env.clone());
+        heap.track_env(&inner);
+        drop(env);
+
+        assert_eq!(heap.collect(), 0);
+        assert!(inner.borrow().enclosing().is_some());
+
+        drop(inner);
+
+        assert_eq!(heap.collect(), 2);
+        assert_eq!(heap.stats().tracked, 0);
+        assert_eq!(heap.stats().collected, 2);
+    }
+}
@@ -0,0 +1,58 @@
+use std::{collections::HashSet, rc::Rc};
+
+use super::ast::identifier::Id;
+
+/// A table of strings, so each distinct string literal and identifier of the source is held once and shared.
+#[derive(Debug, Default)]
+pub struct Interner {
+    strings: HashSet<Rc<str>>,
+}
+
+impl Interner {
+    /// The interned string equal to `s`, interning `s` if not already interned.
+    pub fn intern(&mut self, s: &str) -> Rc<str> {
+        match self.strings.get(s) {
+            Some(interned) => interned.clone(),
+
+            None => {
+                let interned: Rc<str> = Rc::from(s);
+                self.strings.insert(interned.clone());
+                interned
+            }
+        }
+    }
+
+    /// The symbol of `name`, interning `name` if not already interned.
+    pub fn symbol(&mut self, name: &str) -> Id {
+        Id::interned(self.intern(name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shared() {
+        let mut interner = Interner::default();
+
+        let a = interner.intern("a");
+        let b = interner.intern("b");
+
+        assert!(Rc::ptr_eq(&a, &interner.intern("a")));
+        assert!(!Rc::ptr_eq(&a, &b));
+    }
+
+    #[test]
+    fn symbols() {
+        let mut interner = Interner::default();
+
+        let a = interner.symbol("a");
+        let name = String::from("a");
+
+        assert_eq!(a, interner.symbol(name.as_str()));
+        assert_ne!(a, interner.symbol("b"));
+        assert!(Rc::ptr_eq(&a.string(), &interner.intern("a")));
+        assert_eq!(&*a, "a");
+    }
+}
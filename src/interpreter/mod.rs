@@ -1,14 +1,21 @@
-use std::io::Write;
+use std::{io::Write, rc::Rc};
 
 pub mod ast;
+pub mod config;
 pub mod location;
 
 pub mod environment;
 pub mod err;
 pub mod evaluation;
+pub mod gc;
+mod interner;
+pub mod natives;
+mod optimise;
 
 mod parser;
-use err::Stumble;
+use config::Config;
+use err::{Stumble, StumbleKind};
+use interner::Interner;
 use location::Location;
 
 mod scanner;
@@ -17,7 +24,8 @@ mod base;
 pub use base::Base;
 
 use ast::{
-    expression::ExprB,
+    expression::{Expr, ExprB},
+    identifier::{Id, Identifier},
     statement::{Statement, Statements},
 };
 use environment::{Env, EnvHandle};
@@ -38,12 +46,27 @@ pub struct TreeWalker {
     tokens: Tkns,
     token_index: usize,
 
+    /// For each interpolation open while scanning, innermost last, the depth of braces opened within the interpolation.
+    interpolations: Vec<usize>,
+
+    /// The labels of the loops enclosing the statement being parsed, innermost last.
+    loop_labels: Vec<Option<Id>>,
+
+    /// The identifiers and string literals of the source.
+    interner: Interner,
+
     parse_env: EnvHandle,
     interpret_env: EnvHandle,
+
+    config: Config,
 }
 
 impl Default for TreeWalker {
     fn default() -> Self {
+        let mut interner = Interner::default();
+        let parse_env = Env::fresh_std_env(&mut interner);
+        let interpret_env = Env::fresh_std_env(&mut interner);
+
         TreeWalker {
             source: Vec::default(),
             line_breaks: vec![0],
@@ -51,136 +74,375 @@ impl Default for TreeWalker {
             parse_location: Location::default(),
             tokens: Vec::default(),
             token_index: 0,
+            interpolations: Vec::default(),
+            loop_labels: Vec::default(),
+            interner,
             statements: Statements::default(),
 
-            parse_env: Env::fresh_std_env(),
-            interpret_env: Env::fresh_std_env(),
+            parse_env,
+            interpret_env,
+
+            config: Config::default(),
+        }
+    }
+}
+
+impl TreeWalker {
+    pub fn with_config(config: Config) -> Self {
+        TreeWalker {
+            config,
+            ..Default::default()
         }
     }
+
+    /// A fresh environment enclosed by a global environment of the natives, with names interned as symbols of the walker.
+    pub fn fresh_std_env(&mut self) -> EnvHandle {
+        Env::fresh_std_env(&mut self.interner)
+    }
 }
 
 #[derive(Debug)]
 pub enum Control {
-    Break,
+    Break { label: Option<Id> },
+    Continue { label: Option<Id> },
     Proceed,
+    Return,
+}
+
+impl Control {
+    /// Whether a break or continue to `target` applies to a loop with `label`.
+    ///
+    /// An unlabelled break or continue applies to the innermost loop.
+    fn targets(target: &Option<Id>, label: &Option<Id>) -> bool {
+        target.is_none() || target == label
+    }
 }
 
 impl TreeWalker {
+    /// Interpret `statement`, returning how the statement exits and the value of the statement.
+    ///
+    /// As with `eval`, each compound statement is interpreted by a distinct method, which keeps the stack frame of `interpret` itself small.
     pub fn interpret(
         &self,
         statement: &Statement,
         env: &EnvHandle,
         base: &mut Base,
     ) -> Result<(Control, ExprB), Stumble> {
+        self.step(base)?;
+
         match statement {
             Statement::Expression { e } => Ok((Control::Proceed, self.eval(e, env, base)?)),
 
-            Statement::Print { e } => {
-                let evaluation = self.eval(e, env, base)?;
+            Statement::Print { e } => self.interpret_print(e, env, base),
 
-                let _ = base.stdio.write(format!("{evaluation}\n").as_bytes());
+            Statement::Declaration { id, e } => self.interpret_declaration(id, e, env, base),
 
-                Ok((Control::Proceed, ExprB::Nil))
+            Statement::Block { statements } => self.interpret_block(statements, env, base),
+
+            Statement::Conditional {
+                condition,
+                case_if,
+                case_else,
+            } => self.interpret_conditional(condition, case_if, case_else, env, base),
+
+            Statement::Loop { label, statements } => {
+                self.interpret_while(label, None, statements, None, env, base)
             }
 
-            Statement::Declaration { id, e } => {
-                let assignment = self.eval(e, env, base)?;
+            Statement::While {
+                label,
+                condition,
+                body,
+                increment,
+            } => self.interpret_while(label, Some(condition), body, increment.as_ref(), env, base),
 
-                env.borrow_mut().insert(id.name(), assignment.clone());
+            Statement::ForIn {
+                location,
+                label,
+                id,
+                iterable,
+                body,
+            } => self.interpret_for_in(*location, label, id, iterable, body, env, base),
 
-                Ok((Control::Proceed, assignment))
-            }
+            Statement::Function {
+                id,
+                parameters,
+                body,
+            } => self.interpret_function(id, parameters, body, env, base),
 
-            Statement::Block { statements } => {
-                let block_env = Env::narrow(env.clone());
+            Statement::Return { expr } => Ok((Control::Return, self.eval(expr, env, base)?)),
 
-                let mut block_return = ExprB::Nil;
-                let mut block_control = Control::Proceed;
+            Statement::Break { label } => Ok((
+                Control::Break {
+                    label: label.clone(),
+                },
+                ExprB::Nil,
+            )),
 
-                for statement in statements {
-                    (block_control, block_return) = self.interpret(statement, &block_env, base)?;
-                }
+            Statement::Continue { label } => Ok((
+                Control::Continue {
+                    label: label.clone(),
+                },
+                ExprB::Nil,
+            )),
+
+            Statement::Throw { location, e } => self.interpret_throw(*location, e, env, base),
 
-                Ok((block_control, block_return))
+            Statement::Try {
+                body,
+                catch,
+                finally,
+            } => self.interpret_try(body, catch, finally, env, base),
+
+            Statement::Empty => Ok((Control::Proceed, ExprB::Nil)),
+
+            _ => todo!("Inpereter todo: {statement:?}"),
+        }
+    }
+
+    fn interpret_print(
+        &self,
+        e: &Expr,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<(Control, ExprB), Stumble> {
+        let evaluation = self.eval(e, env, base)?;
+
+        let _ = base.stdio.write(format!("{evaluation}\n").as_bytes());
+
+        Ok((Control::Proceed, ExprB::Nil))
+    }
+
+    fn interpret_declaration(
+        &self,
+        id: &Identifier,
+        e: &Expr,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<(Control, ExprB), Stumble> {
+        let assignment = self.eval(e, env, base)?;
+
+        self.bind(env, id, assignment.clone(), base)?;
+
+        Ok((Control::Proceed, assignment))
+    }
+
+    fn interpret_block(
+        &self,
+        statements: &Statements,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<(Control, ExprB), Stumble> {
+        let block_env = self.narrow(env, base)?;
+
+        self.interpret_sequence(statements, &block_env, base)
+    }
+
+    fn interpret_function(
+        &self,
+        id: &Identifier,
+        parameters: &Rc<[Identifier]>,
+        body: &Rc<Statements>,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<(Control, ExprB), Stumble> {
+        // Each evaluation of a declaration is a distinct function, though the parameters and body are shared.
+        let lambda = ExprB::mk_lambda(
+            id.name().string(),
+            env.clone(),
+            parameters.clone(),
+            body.clone(),
+        );
+        let lambda = self.allocated(lambda, base)?;
+
+        self.bind(env, id, lambda, base)?;
+
+        Ok((Control::Proceed, ExprB::Nil))
+    }
+
+    fn interpret_conditional(
+        &self,
+        condition: &Expr,
+        case_if: &Statement,
+        case_else: &Option<Box<Statement>>,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<(Control, ExprB), Stumble> {
+        if self.eval(condition, env, base)?.is_truthy() {
+            self.interpret(case_if, env, base)
+        } else if let Some(otherwise) = case_else {
+            self.interpret(otherwise, env, base)
+        } else {
+            Ok((Control::Proceed, ExprB::Nil))
+        }
+    }
+
+    /// A loop over `body` while `condition` holds, or without end if there is no condition.
+    fn interpret_while(
+        &self,
+        label: &Option<Id>,
+        condition: Option<&Expr>,
+        body: &Statements,
+        increment: Option<&Expr>,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<(Control, ExprB), Stumble> {
+        let block_env = self.narrow(env, base)?;
+
+        loop {
+            if let Some(condition) = condition
+                && self.eval(condition, &block_env, base)?.is_falsey()
+            {
+                return Ok((Control::Proceed, ExprB::Nil));
             }
 
-            Statement::Conditional {
-                condition,
-                case_if,
-                case_else,
-            } => {
-                if self.eval(condition, env, base)?.is_truthy() {
-                    Ok(self.interpret(case_if, env, base)?)
-                } else if let Some(otherwise) = case_else {
-                    Ok(self.interpret(otherwise, env, base)?)
-                } else {
-                    Ok((Control::Proceed, ExprB::Nil))
-                }
+            if let Some(exit) = self.interpret_pass(label, body, &block_env, base)? {
+                return Ok(exit);
             }
 
-            Statement::Loop { statements } => {
-                let block_env = Env::narrow(env.clone());
+            if let Some(increment) = increment {
+                self.eval(increment, &block_env, base)?;
+            }
+        }
+    }
 
-                let mut block_ctl;
-                let mut block_rtn;
+    #[allow(clippy::too_many_arguments)]
+    fn interpret_for_in(
+        &self,
+        location: Location,
+        label: &Option<Id>,
+        id: &Identifier,
+        iterable: &Expr,
+        body: &Statements,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<(Control, ExprB), Stumble> {
+        let iterable = self.eval(iterable, env, base)?;
+        let mut iteration = self.iteration(iterable)?;
 
-                'loop_loop: loop {
-                    for statement in statements {
-                        (block_ctl, block_rtn) = self.interpret(statement, &block_env, base)?;
+        while let Some(value) = self.iterate(&mut iteration, location, base)? {
+            let iteration_env = self.narrow(env, base)?;
+            self.bind(&iteration_env, id, value, base)?;
 
-                        match block_ctl {
-                            Control::Break => {
-                                block_ctl = Control::Proceed; // The break has been handled.
-                                break 'loop_loop;
-                            }
+            if let Some(exit) = self.interpret_pass(label, body, &iteration_env, base)? {
+                return Ok(exit);
+            }
+        }
 
-                            Control::Proceed => {}
-                        };
-                    }
-                }
+        Ok((Control::Proceed, ExprB::Nil))
+    }
 
-                Ok((block_ctl, block_rtn))
+    fn interpret_throw(
+        &self,
+        location: Location,
+        e: &Expr,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<(Control, ExprB), Stumble> {
+        let value = self.eval(e, env, base)?;
+
+        Err(Stumble {
+            location,
+            kind: StumbleKind::Thrown { value },
+            located: true,
+            trace: Vec::default(),
+        })
+    }
+
+    fn interpret_try(
+        &self,
+        body: &Statements,
+        catch: &Option<(Identifier, Statements)>,
+        finally: &Option<Statements>,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<(Control, ExprB), Stumble> {
+        let body_env = self.narrow(env, base)?;
+
+        let exit = match (catch, self.interpret_sequence(body, &body_env, base)) {
+            (Some((id, statements)), Err(stumble)) if stumble.kind.is_catchable() => {
+                let catch_env = self.narrow(env, base)?;
+                self.bind(&catch_env, id, stumble.into_value(), base)?;
+
+                self.interpret_sequence(statements, &catch_env, base)
             }
 
-            Statement::While { condition, body } => {
-                // TODO: Avoid a fresh block each time?
+            (_, exit) => exit,
+        };
 
-                let mut loops = vec![];
+        // The termination of evaluation by the host is not interpreted through a finally, which could otherwise take its place.
+        if let Err(stumble) = &exit
+            && !stumble.kind.is_catchable()
+        {
+            return exit;
+        }
 
-                loops.push(Statement::mk_conditional(
-                    condition.clone(),
-                    Statement::Empty,
-                    Some(Statement::Break),
-                ));
-                loops.extend_from_slice(body);
+        // A finally which itself exits, by an error or otherwise, takes the place of the prior exit.
+        if let Some(statements) = finally {
+            let finally_env = self.narrow(env, base)?;
 
-                self.interpret(&Statement::mk_loop(loops), env, base)
+            match self.interpret_sequence(statements, &finally_env, base)? {
+                (Control::Proceed, _) => {}
+
+                finally_exit => return Ok(finally_exit),
             }
+        }
 
-            Statement::Function {
-                id,
-                parameters,
-                body,
-            } => {
-                let lambda = ExprB::Lambda {
-                    env: env.clone(),
-                    params: parameters.clone(),
-                    body: body.clone(),
-                };
+        exit
+    }
+
+    /// Interpret each of `statements` in `env`, until a statement exits with a control other than proceed.
+    ///
+    /// The value is that of the last statement interpreted.
+    fn interpret_sequence(
+        &self,
+        statements: &Statements,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<(Control, ExprB), Stumble> {
+        let mut value = ExprB::Nil;
 
-                env.borrow_mut().insert(id.name(), lambda);
+        for statement in statements {
+            let control;
+            (control, value) = self.interpret(statement, env, base)?;
 
-                Ok((Control::Proceed, ExprB::Nil))
+            if !matches!(control, Control::Proceed) {
+                return Ok((control, value));
             }
+        }
 
-            Statement::Return { expr } => Ok((Control::Proceed, self.eval(expr, env, base)?)),
+        Ok((Control::Proceed, value))
+    }
 
-            Statement::Break => Ok((Control::Break, ExprB::Nil)),
+    /// Interpret a single pass over the `body` of a loop with `label`.
+    ///
+    /// Returns the control and value the loop exits with, if the pass ends the loop.
+    fn interpret_pass(
+        &self,
+        label: &Option<Id>,
+        body: &Statements,
+        env: &EnvHandle,
+        base: &mut Base,
+    ) -> Result<Option<(Control, ExprB)>, Stumble> {
+        self.step(base)?;
 
-            Statement::Empty => Ok((Control::Proceed, ExprB::Nil)),
+        for statement in body {
+            match self.interpret(statement, env, base)? {
+                (Control::Proceed, _) => {}
 
-            _ => todo!("Inpereter todo: {statement:?}"),
+                (Control::Break { label: target }, _) if Control::targets(&target, label) => {
+                    return Ok(Some((Control::Proceed, ExprB::Nil)));
+                }
+
+                (Control::Continue { label: target }, _) if Control::targets(&target, label) => {
+                    return Ok(None);
+                }
+
+                exit => return Ok(Some(exit)),
+            }
         }
+
+        Ok(None)
     }
 
     pub fn interpret_all(&self, base: &mut Base) -> Result<(), Stumble> {
@@ -0,0 +1,64 @@
+use crate::interpreter::{
+    Base, TreeWalker,
+    ast::expression::{ExprB, MapKey},
+    err::StumbleKind,
+};
+
+use super::{Native, expect_map};
+
+pub fn natives() -> Vec<Native> {
+    vec![
+        Native::new("keys", 1, keys),
+        Native::new("values", 1, values),
+        Native::new("has", 2, has),
+        Native::new("delete", 2, delete),
+        Native::new("len", 1, len),
+    ]
+}
+
+fn key(expr: &ExprB) -> Result<MapKey, StumbleKind> {
+    MapKey::from_expr(expr).ok_or(StumbleKind::UnhashableKey)
+}
+
+/// The keys of a map, as a list in insertion order.
+fn keys(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let map = expect_map(&args[0])?;
+    let keys = map.borrow().keys().map(MapKey::to_expr).collect();
+    Ok(ExprB::mk_list(keys))
+}
+
+/// The values of a map, as a list in insertion order.
+fn values(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let map = expect_map(&args[0])?;
+    let values = map.borrow().values().cloned().collect();
+    Ok(ExprB::mk_list(values))
+}
+
+/// Whether a map contains a key.
+fn has(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let map = expect_map(&args[0])?;
+    let key = key(&args[1])?;
+    Ok(ExprB::mk_bool(map.borrow().contains_key(&key)))
+}
+
+/// Remove a key from a map, returning the value of the key or nil if the key was absent.
+fn delete(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let map = expect_map(&args[0])?;
+    let key = key(&args[1])?;
+    Ok(map.borrow_mut().remove(&key).unwrap_or(ExprB::Nil))
+}
+
+/// The number of elements in a list, entries in a map, or characters in a string.
+fn len(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let length = match &args[0] {
+        ExprB::String { s } => s.chars().count(),
+
+        ExprB::List { l } => l.borrow().len(),
+
+        ExprB::Map { m } => m.borrow().len(),
+
+        _ => return Err(StumbleKind::ConflictingSubexpression),
+    };
+
+    Ok(ExprB::mk_integer(length as i64))
+}
@@ -0,0 +1,13 @@
+use crate::interpreter::{Base, TreeWalker, ast::expression::ExprB, err::StumbleKind};
+
+use super::Native;
+
+pub fn natives() -> Vec<Native> {
+    vec![Native::new("gc", 0, gc)]
+}
+
+/// Collect reference cycles, returning the number of environments, lists, and maps freed.
+fn gc(_: &TreeWalker, base: &mut Base, _: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let collected = base.heap.collect();
+    Ok(ExprB::mk_integer(collected as i64))
+}
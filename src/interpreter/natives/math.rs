@@ -0,0 +1,197 @@
+use std::cmp::Ordering;
+
+use crate::interpreter::{
+    Base, TreeWalker,
+    ast::expression::{ExprB, Map, MapKey, Number, OpTwo},
+    err::StumbleKind,
+    evaluation::arithmetic::{arithmetic, negate},
+};
+
+use super::{Native, expect_number};
+
+/// The `math` namespace, a map from the names of mathematical functions and constants to their values.
+pub fn namespace() -> Map {
+    let mut namespace = Map::default();
+
+    for native in natives() {
+        let key = MapKey::String {
+            s: native.name.into(),
+        };
+        namespace.insert(key, ExprB::Native { native });
+    }
+
+    let constants = [
+        ("PI", std::f64::consts::PI),
+        ("E", std::f64::consts::E),
+        ("INF", f64::INFINITY),
+        ("NAN", f64::NAN),
+    ];
+
+    for (name, n) in constants {
+        let key = MapKey::String { s: name.into() };
+        namespace.insert(key, ExprB::mk_numeric(n));
+    }
+
+    namespace
+}
+
+fn natives() -> Vec<Native> {
+    vec![
+        Native::new("sqrt", 1, sqrt),
+        Native::new("pow", 2, pow),
+        Native::new("floor", 1, floor),
+        Native::new("ceil", 1, ceil),
+        Native::new("round", 1, round),
+        Native::new("abs", 1, abs),
+        Native::new("min", 2, min),
+        Native::new("max", 2, max),
+        Native::new("sin", 1, sin),
+        Native::new("cos", 1, cos),
+        Native::new("tan", 1, tan),
+        Native::new("asin", 1, asin),
+        Native::new("acos", 1, acos),
+        Native::new("atan", 1, atan),
+        Native::new("atan2", 2, atan2),
+        Native::new("log", 1, log),
+        Native::new("log2", 1, log2),
+        Native::new("log10", 1, log10),
+        Native::new("exp", 1, exp),
+        Native::new("is_nan", 1, is_nan),
+    ]
+}
+
+/// The result of `f` applied to the only argument, as a float.
+fn float_fn(args: &[ExprB], f: fn(f64) -> f64) -> Result<ExprB, StumbleKind> {
+    let n = expect_number(&args[0])?.to_f64();
+    Ok(ExprB::mk_numeric(f(n)))
+}
+
+/// The result of `f` applied to the only argument if a float, and otherwise the integral argument.
+fn integral_fn(args: &[ExprB], f: fn(f64) -> f64) -> Result<ExprB, StumbleKind> {
+    match expect_number(&args[0])? {
+        Number::Float(n) => Ok(ExprB::mk_numeric(f(n))),
+
+        integer => Ok(integer.to_expr()),
+    }
+}
+
+fn sqrt(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    float_fn(&args, f64::sqrt)
+}
+
+/// A base raised to an exponent, as with `**`.
+fn pow(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let base = expect_number(&args[0])?;
+    let exponent = expect_number(&args[1])?;
+    Ok(arithmetic(OpTwo::StarStar, base, exponent)?.to_expr())
+}
+
+/// The greatest integer at most the argument, as a float if the argument is a float.
+fn floor(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    integral_fn(&args, f64::floor)
+}
+
+/// The least integer at least the argument, as a float if the argument is a float.
+fn ceil(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    integral_fn(&args, f64::ceil)
+}
+
+/// The nearest integer to the argument, with halves rounded away from zero, as a float if the argument is a float.
+fn round(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    integral_fn(&args, f64::round)
+}
+
+fn abs(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let n = expect_number(&args[0])?;
+
+    let magnitude = match n.compare(&Number::Integer(0)) {
+        Some(Ordering::Less) => negate(n),
+
+        _ => match n {
+            Number::Float(n) => Number::Float(n.abs()),
+
+            n => n,
+        },
+    };
+
+    Ok(magnitude.to_expr())
+}
+
+/// The lesser of two numbers, or `NaN` if the numbers are unordered.
+fn min(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    match expect_number(&args[0])?.compare(&expect_number(&args[1])?) {
+        Some(Ordering::Less | Ordering::Equal) => Ok(args[0].clone()),
+
+        Some(Ordering::Greater) => Ok(args[1].clone()),
+
+        None => Ok(ExprB::mk_numeric(f64::NAN)),
+    }
+}
+
+/// The greater of two numbers, or `NaN` if the numbers are unordered.
+fn max(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    match expect_number(&args[0])?.compare(&expect_number(&args[1])?) {
+        Some(Ordering::Greater | Ordering::Equal) => Ok(args[0].clone()),
+
+        Some(Ordering::Less) => Ok(args[1].clone()),
+
+        None => Ok(ExprB::mk_numeric(f64::NAN)),
+    }
+}
+
+fn sin(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    float_fn(&args, f64::sin)
+}
+
+fn cos(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    float_fn(&args, f64::cos)
+}
+
+fn tan(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    float_fn(&args, f64::tan)
+}
+
+fn asin(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    float_fn(&args, f64::asin)
+}
+
+fn acos(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    float_fn(&args, f64::acos)
+}
+
+fn atan(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    float_fn(&args, f64::atan)
+}
+
+/// The angle of the point `(x, y)`, given as `atan2(y, x)`.
+fn atan2(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let y = expect_number(&args[0])?.to_f64();
+    let x = expect_number(&args[1])?.to_f64();
+    Ok(ExprB::mk_numeric(y.atan2(x)))
+}
+
+/// The natural logarithm.
+fn log(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    float_fn(&args, f64::ln)
+}
+
+fn log2(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    float_fn(&args, f64::log2)
+}
+
+fn log10(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    float_fn(&args, f64::log10)
+}
+
+fn exp(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    float_fn(&args, f64::exp)
+}
+
+/// Whether a number is `NaN`, which as `NaN` is not equal to itself cannot be found with `==`.
+fn is_nan(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    match expect_number(&args[0])? {
+        Number::Float(n) => Ok(ExprB::mk_bool(n.is_nan())),
+
+        _ => Ok(ExprB::mk_bool(false)),
+    }
+}
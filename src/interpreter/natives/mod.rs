@@ -0,0 +1,94 @@
+use crate::interpreter::{
+    Base, TreeWalker,
+    ast::expression::{ExprB, ListHandle, MapHandle, Number},
+    environment::Env,
+    err::StumbleKind,
+    interner::Interner,
+};
+
+mod collections;
+mod heap;
+mod math;
+mod numbers;
+mod strings;
+mod values;
+
+pub type NativeFn = fn(&TreeWalker, &mut Base, Vec<ExprB>) -> Result<ExprB, StumbleKind>;
+
+/// A function implemented by the interpreter, called with already evaluated arguments.
+///
+/// A method is bound to the value it is a method of, which is passed to `f` as the first argument, ahead of the `arity` arguments of a call.
+#[derive(Clone, Debug)]
+pub struct Native {
+    pub name: &'static str,
+    pub arity: usize,
+    pub f: NativeFn,
+    pub receiver: Option<Box<ExprB>>,
+}
+
+impl Native {
+    pub fn new(name: &'static str, arity: usize, f: NativeFn) -> Self {
+        Native {
+            name,
+            arity,
+            f,
+            receiver: None,
+        }
+    }
+
+    pub fn bind(self, receiver: ExprB) -> Self {
+        Native {
+            receiver: Some(Box::new(receiver)),
+            ..self
+        }
+    }
+}
+
+/// Register each native function in `env`, along with namespaces of native functions, with names interned by `interner`.
+pub(crate) fn register(env: &mut Env, interner: &mut Interner) {
+    for native in collections::natives()
+        .into_iter()
+        .chain(heap::natives())
+        .chain(numbers::natives())
+        .chain(values::natives())
+    {
+        env.insert(&interner.symbol(native.name), ExprB::Native { native });
+    }
+
+    env.insert(&interner.symbol("math"), ExprB::mk_map(math::namespace()));
+}
+
+/// The method `name` of strings, if any.
+pub fn string_method(name: &str) -> Option<Native> {
+    strings::methods()
+        .into_iter()
+        .find(|method| method.name == name)
+}
+
+pub fn expect_list(expr: &ExprB) -> Result<ListHandle, StumbleKind> {
+    match expr {
+        ExprB::List { l } => Ok(l.clone()),
+
+        _ => Err(StumbleKind::ConflictingSubexpression),
+    }
+}
+
+pub fn expect_map(expr: &ExprB) -> Result<MapHandle, StumbleKind> {
+    match expr {
+        ExprB::Map { m } => Ok(m.clone()),
+
+        _ => Err(StumbleKind::ConflictingSubexpression),
+    }
+}
+
+pub fn expect_number(expr: &ExprB) -> Result<Number, StumbleKind> {
+    expr.number().ok_or(StumbleKind::ConflictingSubexpression)
+}
+
+pub fn expect_string(expr: &ExprB) -> Result<&str, StumbleKind> {
+    match expr {
+        ExprB::String { s } => Ok(s),
+
+        _ => Err(StumbleKind::ConflictingSubexpression),
+    }
+}
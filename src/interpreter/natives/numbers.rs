@@ -0,0 +1,55 @@
+use crate::interpreter::{
+    Base, TreeWalker,
+    ast::expression::{BigInt, ExprB, Number, number::integral_float},
+    err::StumbleKind,
+};
+
+use super::Native;
+
+pub fn natives() -> Vec<Native> {
+    vec![Native::new("int", 1, int), Native::new("float", 1, float)]
+}
+
+/// A number or string as an integer.
+///
+/// Floats are truncated towards zero, and strings must be written as an integer.
+fn int(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    match &args[0] {
+        i @ (ExprB::BigInteger { .. } | ExprB::Integer { .. }) => Ok(i.clone()),
+
+        ExprB::Numeric { n } => match integral_float(n.trunc()) {
+            Some(i) => Ok(i.to_expr()),
+
+            None => Err(StumbleKind::InvalidConversion),
+        },
+
+        ExprB::String { s } => match BigInt::from_str_radix(s.trim(), 10) {
+            Some(i) => Ok(Number::from(i).to_expr()),
+
+            None => Err(StumbleKind::InvalidConversion),
+        },
+
+        _ => Err(StumbleKind::ConflictingSubexpression),
+    }
+}
+
+/// A number or string as a float.
+///
+/// Integers beyond 2^53 are rounded to the nearest float, and so integers beyond the range of a float are infinite.
+fn float(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    match &args[0] {
+        ExprB::BigInteger { i } => Ok(ExprB::mk_numeric(i.to_f64())),
+
+        ExprB::Integer { i } => Ok(ExprB::mk_numeric(*i as f64)),
+
+        ExprB::Numeric { n } => Ok(ExprB::mk_numeric(*n)),
+
+        ExprB::String { s } => match s.trim().parse() {
+            Ok(n) => Ok(ExprB::mk_numeric(n)),
+
+            Err(_) => Err(StumbleKind::InvalidConversion),
+        },
+
+        _ => Err(StumbleKind::ConflictingSubexpression),
+    }
+}
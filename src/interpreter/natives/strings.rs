@@ -0,0 +1,142 @@
+use crate::interpreter::{
+    Base, TreeWalker,
+    ast::expression::{ExprB, Number},
+    err::StumbleKind,
+};
+
+use super::{Native, expect_string};
+
+/// The methods of strings, each of which takes the string as the first argument.
+///
+/// Lengths and indices count characters, rather than bytes.
+/// A method whose result may be much larger than the string checks the size of the result against the memory quota before building the result.
+pub fn methods() -> Vec<Native> {
+    vec![
+        Native::new("len", 0, len),
+        Native::new("upper", 0, upper),
+        Native::new("lower", 0, lower),
+        Native::new("split", 1, split),
+        Native::new("trim", 0, trim),
+        Native::new("contains", 1, contains),
+        Native::new("replace", 2, replace),
+        Native::new("substr", 2, substr),
+        Native::new("chars", 0, chars),
+    ]
+}
+
+/// The number of characters in the string.
+fn len(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let s = expect_string(&args[0])?;
+    Ok(ExprB::mk_integer(s.chars().count() as i64))
+}
+
+fn upper(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let s = expect_string(&args[0])?;
+    Ok(ExprB::mk_string(s.to_uppercase()))
+}
+
+fn lower(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let s = expect_string(&args[0])?;
+    Ok(ExprB::mk_string(s.to_lowercase()))
+}
+
+/// The parts of the string separated by a separator, as a list.
+///
+/// An empty separator splits the string into characters.
+fn split(walker: &TreeWalker, base: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let s = expect_string(&args[0])?;
+
+    match expect_string(&args[1])? {
+        "" => chars_list(walker, base, s),
+
+        separator => {
+            let parts = s.matches(separator).count() + 1;
+            walker.reserve(list_size(parts, s.len()), base)?;
+
+            let parts = s.split(separator);
+            Ok(ExprB::mk_list(
+                parts
+                    .map(|part| ExprB::mk_string(part.to_owned()))
+                    .collect(),
+            ))
+        }
+    }
+}
+
+/// The string without leading and trailing whitespace.
+fn trim(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let s = expect_string(&args[0])?;
+    Ok(ExprB::mk_string(s.trim().to_owned()))
+}
+
+/// Whether the string contains a substring.
+fn contains(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let s = expect_string(&args[0])?;
+    let substring = expect_string(&args[1])?;
+    Ok(ExprB::mk_bool(s.contains(substring)))
+}
+
+/// The string with each occurrence of a substring replaced.
+fn replace(walker: &TreeWalker, base: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let s = expect_string(&args[0])?;
+    let from = expect_string(&args[1])?;
+    let to = expect_string(&args[2])?;
+
+    let occurrences = s.matches(from).count();
+    let size =
+        (s.len() - occurrences * from.len()).saturating_add(occurrences.saturating_mul(to.len()));
+    walker.reserve(size, base)?;
+
+    Ok(ExprB::mk_string(s.replace(from, to)))
+}
+
+/// The characters of the string from a start index up to, but not including, an end index.
+fn substr(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    let s = expect_string(&args[0])?;
+    let length = s.chars().count();
+
+    let start = char_index(&args[1], length)?;
+    let end = char_index(&args[2], length)?;
+    if end < start {
+        return Err(StumbleKind::IndexOutOfBounds {
+            index: start,
+            length,
+        });
+    }
+
+    Ok(ExprB::mk_string(
+        s.chars().skip(start).take(end - start).collect::<String>(),
+    ))
+}
+
+/// The characters of the string, as a list of strings.
+fn chars(walker: &TreeWalker, base: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    chars_list(walker, base, expect_string(&args[0])?)
+}
+
+fn chars_list(walker: &TreeWalker, base: &mut Base, s: &str) -> Result<ExprB, StumbleKind> {
+    walker.reserve(list_size(s.chars().count(), s.len()), base)?;
+
+    let chars = s.chars().map(|c| ExprB::mk_string(c.to_string())).collect();
+    Ok(ExprB::mk_list(chars))
+}
+
+/// The number of bytes held by a list of `elements` strings, of `bytes` bytes in total.
+fn list_size(elements: usize, bytes: usize) -> usize {
+    elements
+        .saturating_mul(std::mem::size_of::<ExprB>())
+        .saturating_add(bytes)
+}
+
+/// An index between characters of a string of `length` characters, and so at most `length`.
+fn char_index(index: &ExprB, length: usize) -> Result<usize, StumbleKind> {
+    match index.number() {
+        Some(Number::Integer(i)) if 0 <= i => match i as usize {
+            index if index <= length => Ok(index),
+
+            index => Err(StumbleKind::IndexOutOfBounds { index, length }),
+        },
+
+        _ => Err(StumbleKind::InvalidIndex),
+    }
+}
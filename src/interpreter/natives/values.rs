@@ -0,0 +1,12 @@
+use crate::interpreter::{Base, TreeWalker, ast::expression::ExprB, err::StumbleKind};
+
+use super::Native;
+
+pub fn natives() -> Vec<Native> {
+    vec![Native::new("same", 2, same)]
+}
+
+/// Whether two values are the same value, as distinct from equal values.
+fn same(_: &TreeWalker, _: &mut Base, args: Vec<ExprB>) -> Result<ExprB, StumbleKind> {
+    Ok(ExprB::mk_bool(args[0].same(&args[1])))
+}
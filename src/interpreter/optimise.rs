@@ -0,0 +1,367 @@
+//! An optional pass over parsed statements, which folds constant expressions and removes statements which are never interpreted.
+//!
+//! A constant operator expression is folded by applying the operator as evaluation would, and so with the same configuration.
+//! An expression which stumbles when applied, such as a division by zero, is left as parsed, and so stumbles when interpreted, at the same location.
+//! An integer power is folded only if small, as a power may be far larger than its operands and so costly to compute while parsing.
+
+use std::rc::Rc;
+
+use crate::interpreter::{
+    TreeWalker,
+    ast::{
+        expression::{Expr, ExprB, Number, OpTwo},
+        statement::{Statement, Statements},
+    },
+};
+
+/// The most bits of an integer power which is folded.
+const MAX_FOLDED_BITS: u64 = 256;
+
+/// Whether `op` applied to the constants `a` and `b` is folded.
+///
+/// The result of each operator other than `**` is bounded by the size of its operands, or to 64 bits for shifts.
+fn folds(op: OpTwo, a: &ExprB, b: &ExprB) -> bool {
+    let Some(base) = a.number().and_then(|a| a.to_big()) else {
+        return true;
+    };
+
+    match (op, b.number()) {
+        (OpTwo::StarStar, Some(Number::Integer(exponent))) => match u64::try_from(exponent) {
+            Ok(exponent) => base.bits().saturating_mul(exponent) <= MAX_FOLDED_BITS,
+
+            // A negative exponent results in a float.
+            Err(_) => true,
+        },
+
+        (OpTwo::StarStar, Some(Number::BigInteger(_))) => false,
+
+        _ => true,
+    }
+}
+
+impl TreeWalker {
+    /// `statement` optimised, or none if interpreting `statement` has no effect.
+    pub fn optimise(&self, statement: Statement) -> Option<Statement> {
+        let statement = match statement {
+            Statement::Empty => return None,
+
+            Statement::Assignment { id, e } => Statement::mk_assignment(id, self.fold(e)),
+
+            Statement::Block { statements } => {
+                Statement::mk_block(self.optimise_sequence(statements))
+            }
+
+            Statement::Conditional {
+                condition,
+                case_if,
+                case_else,
+            } => return self.optimise_conditional(condition, *case_if, case_else),
+
+            Statement::Declaration { id, e } => Statement::mk_declaration(id, self.fold(e)),
+
+            Statement::Expression { e } => Statement::mk_expression(self.fold(e)),
+
+            Statement::ForIn {
+                location,
+                label,
+                id,
+                iterable,
+                body,
+            } => Statement::ForIn {
+                location,
+                label,
+                id,
+                iterable: self.fold(iterable),
+                body: self.optimise_sequence(body),
+            },
+
+            Statement::Function {
+                id,
+                parameters,
+                body,
+            } => Statement::Function {
+                id,
+                parameters,
+                body: Rc::new(self.optimise_sequence(Rc::unwrap_or_clone(body))),
+            },
+
+            Statement::Print { e } => Statement::mk_print(self.fold(e)),
+
+            Statement::Return { expr } => Statement::Return {
+                expr: self.fold(expr),
+            },
+
+            Statement::Throw { location, e } => Statement::Throw {
+                location,
+                e: self.fold(e),
+            },
+
+            Statement::Try {
+                body,
+                catch,
+                finally,
+            } => Statement::Try {
+                body: self.optimise_sequence(body),
+                catch: catch.map(|(id, statements)| (id, self.optimise_sequence(statements))),
+                finally: finally.map(|statements| self.optimise_sequence(statements)),
+            },
+
+            Statement::While {
+                label,
+                condition,
+                body,
+                increment,
+            } => Statement::While {
+                label,
+                condition: self.fold(condition),
+                body: self.optimise_sequence(body),
+                increment: increment.map(|e| self.fold(e)),
+            },
+
+            Statement::Loop { label, statements } => Statement::Loop {
+                label,
+                statements: self.optimise_sequence(statements),
+            },
+
+            statement @ (Statement::Break { .. } | Statement::Continue { .. }) => statement,
+        };
+
+        Some(statement)
+    }
+
+    /// Each of `statements` optimised, up to the first statement which unconditionally exits the sequence.
+    pub fn optimise_sequence(&self, statements: Statements) -> Statements {
+        let mut optimised = Statements::with_capacity(statements.len());
+
+        for statement in statements {
+            let Some(statement) = self.optimise(statement) else {
+                continue;
+            };
+
+            let exits = matches!(
+                statement,
+                Statement::Break { .. }
+                    | Statement::Continue { .. }
+                    | Statement::Return { .. }
+                    | Statement::Throw { .. }
+            );
+
+            optimised.push(statement);
+
+            if exits {
+                break;
+            }
+        }
+
+        optimised
+    }
+
+    /// A conditional optimised, with only the case taken if the condition is constant.
+    fn optimise_conditional(
+        &self,
+        condition: Expr,
+        case_if: Statement,
+        case_else: Option<Box<Statement>>,
+    ) -> Option<Statement> {
+        match self.fold(condition) {
+            Expr::Basic(value) => match value.is_truthy() {
+                true => self.optimise(case_if),
+
+                false => case_else.and_then(|case_else| self.optimise(*case_else)),
+            },
+
+            condition => Some(Statement::Conditional {
+                condition,
+                case_if: Box::new(self.optimise(case_if).unwrap_or(Statement::Empty)),
+                case_else: case_else
+                    .and_then(|case_else| self.optimise(*case_else))
+                    .map(Box::new),
+            }),
+        }
+    }
+
+    /// `expr` with each constant unary, binary, and grouping expression folded to its value.
+    ///
+    /// A location is kept only while the expression it locates may stumble.
+    pub fn fold(&self, expr: Expr) -> Expr {
+        match expr {
+            Expr::Grouping { e } => match self.fold(*e) {
+                Expr::Basic(value) => Expr::Basic(value),
+
+                e => Expr::Grouping { e: Box::new(e) },
+            },
+
+            Expr::Unary { op, e } => match self.fold(*e) {
+                Expr::Basic(value) => match self.unary_value(op, value.clone()) {
+                    Ok(folded) => Expr::Basic(folded),
+
+                    Err(_) => Expr::mk_unary(op, Expr::Basic(value)),
+                },
+
+                e => Expr::mk_unary(op, e),
+            },
+
+            Expr::Binary { op, a, b } => match (self.fold(*a), self.fold(*b)) {
+                (Expr::Basic(a), Expr::Basic(b)) if !folds(op, &a, &b) => {
+                    Expr::mk_binary(op, Expr::Basic(a), Expr::Basic(b))
+                }
+
+                (Expr::Basic(a), Expr::Basic(b)) => match self.binary(op, a.clone(), b.clone()) {
+                    Ok(folded) => Expr::Basic(folded),
+
+                    Err(_) => Expr::mk_binary(op, Expr::Basic(a), Expr::Basic(b)),
+                },
+
+                (a, b) => Expr::mk_binary(op, a, b),
+            },
+
+            Expr::Located { location, e } => match self.fold(*e) {
+                Expr::Basic(value) => Expr::Basic(value),
+
+                e => Expr::mk_located(location, e),
+            },
+
+            Expr::Assignment { id, e } => Expr::Assignment {
+                id,
+                e: Box::new(self.fold(*e)),
+            },
+
+            Expr::Update {
+                op,
+                target,
+                e,
+                postfix,
+            } => Expr::Update {
+                op,
+                target,
+                e: Box::new(self.fold(*e)),
+                postfix,
+            },
+
+            Expr::Ternary {
+                condition,
+                case_if,
+                case_else,
+            } => Expr::Ternary {
+                condition: Box::new(self.fold(*condition)),
+                case_if: Box::new(self.fold(*case_if)),
+                case_else: Box::new(self.fold(*case_else)),
+            },
+
+            Expr::Or { a, b } => Expr::Or {
+                a: Box::new(self.fold(*a)),
+                b: Box::new(self.fold(*b)),
+            },
+
+            Expr::And { a, b } => Expr::And {
+                a: Box::new(self.fold(*a)),
+                b: Box::new(self.fold(*b)),
+            },
+
+            Expr::Call {
+                caller,
+                args,
+                location,
+            } => Expr::Call {
+                caller: Box::new(self.fold(*caller)),
+                args: args.into_iter().map(|arg| self.fold(arg)).collect(),
+                location,
+            },
+
+            Expr::List { elements } => Expr::List {
+                elements: elements.into_iter().map(|e| self.fold(e)).collect(),
+            },
+
+            Expr::Map { entries } => Expr::Map {
+                entries: entries
+                    .into_iter()
+                    .map(|(key, value)| (self.fold(key), self.fold(value)))
+                    .collect(),
+            },
+
+            Expr::Index { target, index } => Expr::Index {
+                target: Box::new(self.fold(*target)),
+                index: Box::new(self.fold(*index)),
+            },
+
+            Expr::Get { target, name } => Expr::Get {
+                target: Box::new(self.fold(*target)),
+                name,
+            },
+
+            Expr::Range {
+                start,
+                end,
+                inclusive,
+            } => Expr::Range {
+                start: Box::new(self.fold(*start)),
+                end: Box::new(self.fold(*end)),
+                inclusive,
+            },
+
+            Expr::Interpolation { parts } => Expr::Interpolation {
+                parts: parts.into_iter().map(|part| self.fold(part)).collect(),
+            },
+
+            expr @ (Expr::Empty | Expr::Basic(_) | Expr::Identifier { .. }) => expr,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::interpreter::{TreeWalker, config::Config};
+
+    fn optimised(input: &str) -> Vec<String> {
+        let mut walker = TreeWalker::with_config(Config {
+            optimise: true,
+            ..Default::default()
+        });
+
+        walker.scan(input);
+        walker.parse().expect("Parser error");
+
+        walker
+            .statements()
+            .iter()
+            .map(|statement| format!("{statement:?}"))
+            .collect()
+    }
+
+    #[test]
+    fn folds() {
+        let statements = optimised("print 2 * 60 * 60; print \"a\" + \"b\"; print -(1 + 2);");
+
+        assert_eq!(statements.len(), 3);
+        assert!(statements[0].contains("Integer { i: 7200 }"));
+        assert!(statements[1].contains("String { s: \"ab\" }"));
+        assert!(statements[2].contains("Integer { i: -3 }"));
+    }
+
+    #[test]
+    fn keeps_large_powers() {
+        let statements = optimised("print 2 ** 10; print 2 ** 3000000; print 3 ** 200;");
+
+        assert!(statements[0].contains("Integer { i: 1024 }"));
+        assert!(statements[1].contains("StarStar"));
+        assert!(statements[2].contains("StarStar"));
+    }
+
+    #[test]
+    fn keeps_stumbles() {
+        let statements = optimised("print 1 % 0;");
+
+        assert!(statements[0].contains("Located"));
+        assert!(statements[0].contains("Percent"));
+    }
+
+    #[test]
+    fn removes_dead_statements() {
+        assert!(optimised(";;; if (false) { print 1; }").is_empty());
+
+        let statements = optimised("fun f() { return 1; print 2; } ;");
+
+        assert_eq!(statements.len(), 1);
+        assert!(!statements[0].contains("Print"));
+    }
+}
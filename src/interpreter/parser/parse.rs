@@ -2,11 +2,12 @@ use crate::interpreter::{
     TreeWalker,
     ast::{
         expression::{Expr, ExprB, OpOne, OpTwo},
-        identifier::Identifier,
+        identifier::{Id, Identifier},
         statement::{Statement, Statements},
     },
     environment::{Env, EnvHandle},
     err::{Stumble, StumbleKind},
+    location::Location,
     scanner::token::TknK,
 };
 
@@ -42,6 +43,16 @@ impl TreeWalker {
         loop {
             match self.declaration(&env) {
                 Ok(stmt) => {
+                    let stmt = match self.config.optimise {
+                        true => match self.optimise(stmt) {
+                            Some(stmt) => stmt,
+
+                            None => continue,
+                        },
+
+                        false => stmt,
+                    };
+
                     statement_count += 1;
                     self.statements.push(stmt)
                 }
@@ -66,7 +77,7 @@ impl TreeWalker {
             let d_val = match self.token_kind() {
                 Some(&TknK::Equal) => {
                     self.consume(&TknK::Equal);
-                    self.expression(env)?
+                    self.located_expression(env)?
                 }
 
                 Some(&TknK::Semicolon) => Expr::Basic(ExprB::Nil),
@@ -74,7 +85,8 @@ impl TreeWalker {
                 _ => return Err(self.stumble_token(StumbleKind::ExpectedAssignment)),
             };
 
-            env.borrow_mut().insert(d_id.name(), ExprB::Nil);
+            let slot = env.borrow_mut().declare(d_id.name());
+            let d_id = Identifier::local(d_id.name, slot);
 
             self.close_statement();
 
@@ -85,6 +97,9 @@ impl TreeWalker {
     }
 
     fn block_statements(&mut self, block_env: &EnvHandle) -> Result<Statements, Stumble> {
+        if !matches!(self.token_kind(), Some(TknK::BraceL)) {
+            return Err(self.stumble_token(StumbleKind::ExpectedBlock));
+        }
         self.consume(&TknK::BraceL);
 
         let mut statements = Vec::default();
@@ -108,17 +123,18 @@ impl TreeWalker {
         };
 
         match token.kind {
-            TknK::Var => panic!("! Handled at high precedence"),
+            // Declarations are handled at a higher precedence, and so are found here only as the branch of a conditional.
+            TknK::Var => return Err(self.stumble_token(StumbleKind::Unexpected(self.token_index))),
 
             TknK::Print => {
                 self.consume(&TknK::Print);
-                let expr = self.expression(env)?;
+                let expr = self.located_expression(env)?;
                 self.close_statement()?;
 
                 stmt = Statement::mk_print(expr);
             }
 
-            TknK::BraceL => {
+            TknK::BraceL if !self.map_ahead() => {
                 let block_env = Env::narrow(env.clone());
 
                 stmt = Statement::Block {
@@ -126,33 +142,168 @@ impl TreeWalker {
                 };
             }
 
-            TknK::If => {
-                self.consume(&TknK::If);
+            TknK::If => stmt = self.if_statement(env)?,
 
-                // TODO: Make parens optional, as they're purely decorative here.
-                self.consume(&TknK::ParenL);
+            TknK::Loop | TknK::While | TknK::For => stmt = self.loop_statement(env, None)?,
 
-                let expr = self.expression(env)?;
+            TknK::Identifier { ref id }
+                if matches!(self.token_kind_ahead(1), Some(TknK::Colon)) =>
+            {
+                let label = id.to_owned();
 
-                self.consume(&TknK::ParenR);
+                unsafe { self.consume_unchecked() };
+                self.consume(&TknK::Colon);
 
-                let case_if = self.declaration(env)?;
+                match self.token_kind() {
+                    Some(TknK::Loop | TknK::While | TknK::For) => {}
 
-                let mut case_else = None;
+                    _ => return Err(self.stumble_token(StumbleKind::ExpectedLoop)),
+                }
+
+                stmt = self.loop_statement(env, Some(label))?;
+            }
 
-                if let Some(t) = self.token() {
-                    if t.kind == TknK::Else {
-                        self.consume(&TknK::Else);
-                        case_else = Some(self.declaration(env)?);
+            TknK::Function => {
+                self.consume(&TknK::Function);
+
+                let id;
+                let params;
+
+                match self.expression(env)? {
+                    Expr::Call { caller, args, .. } => {
+                        id = self.to_identifier(*caller)?;
+                        params = self.to_identifiers(args)?;
                     }
-                }
 
-                self.close_statement();
+                    _ => {
+                        return Err(self.stumble_token(StumbleKind::Unexpected(self.token_index)));
+                    }
+                };
+
+                let slot = env.borrow_mut().declare(id.name());
+                let id = Identifier::local(id.name, slot);
+
+                let lambda_env = Env::narrow(env.clone());
+                let params = {
+                    let mut e = lambda_env.borrow_mut();
+                    params
+                        .into_iter()
+                        .map(|p| {
+                            let slot = e.declare(p.name());
+                            Identifier::local(p.name, slot)
+                        })
+                        .collect()
+                };
+
+                // A break or continue within the body may not jump out of the function.
+                let enclosing_loops = std::mem::take(&mut self.loop_labels);
+                let body = self.statement(&lambda_env);
+                self.loop_labels = enclosing_loops;
+
+                let body = match body? {
+                    Statement::Block { statements } => statements,
+
+                    _ => return Err(self.stumble_token(StumbleKind::ExpectedBlock)),
+                };
+
+                stmt = Statement::mk_function(id, params, body);
+            }
+
+            TknK::Semicolon => {
+                self.consume(&TknK::Semicolon);
+                stmt = Statement::Empty
+            }
+
+            TknK::Break => {
+                let label = self.jump_label(StumbleKind::BreakOutsideLoop)?;
+                self.close_statement()?;
+
+                stmt = Statement::mk_break(label);
+            }
+
+            TknK::Continue => {
+                let label = self.jump_label(StumbleKind::ContinueOutsideLoop)?;
+                self.close_statement()?;
+
+                stmt = Statement::mk_continue(label);
+            }
+
+            TknK::Return => {
+                self.consume(&TknK::Return);
+                let location = self.location()?;
+                let rexpr =
+                    Expr::mk_located(location, self.expression_delimited(env, &TknK::Semicolon)?);
+                self.consume(&TknK::Semicolon);
+                stmt = Statement::Return { expr: rexpr }
+            }
+
+            TknK::Throw => {
+                let location = token.location;
+                self.consume(&TknK::Throw);
+                let expr = self.located_expression(env)?;
+                self.close_statement()?;
+
+                stmt = Statement::mk_throw(location, expr);
+            }
+
+            TknK::Try => stmt = self.try_statement(env)?,
+
+            _ => {
+                let expr = self.located_expression(env)?;
+                self.close_statement()?;
+                stmt = Statement::mk_expression(expr);
+            }
+        }
+
+        Ok(stmt)
+    }
+
+    /// An `if` statement, from the keyword.
+    ///
+    /// Parentheses around the condition are optional, though if the condition is not parenthesised the branch must be a block.
+    /// An `else` may be followed by any statement, and so by a further `if` statement to form an `else if` chain.
+    fn if_statement(&mut self, env: &EnvHandle) -> Result<Statement, Stumble> {
+        self.consume(&TknK::If);
+
+        let parenthesised = matches!(self.token_kind(), Some(TknK::ParenL));
+        let condition = self.located_expression(env)?;
+
+        let case_if = match self.token_kind() {
+            Some(TknK::BraceL) => self.statement(env)?,
+
+            _ if parenthesised => self.statement(env)?,
+
+            _ => return Err(self.stumble_token(StumbleKind::ExpectedBlock)),
+        };
 
-                stmt = Statement::mk_conditional(expr, case_if, case_else);
+        let case_else = match self.token_kind() {
+            Some(TknK::Else) => {
+                self.consume(&TknK::Else);
+                Some(self.statement(env)?)
             }
 
-            TknK::Loop => {
+            _ => None,
+        };
+
+        Ok(Statement::mk_conditional(condition, case_if, case_else))
+    }
+
+    /// A `loop`, `while`, or `for` statement with `label`, from the keyword.
+    fn loop_statement(&mut self, env: &EnvHandle, label: Option<Id>) -> Result<Statement, Stumble> {
+        self.loop_labels.push(label.clone());
+        let stmt = self.loop_statement_body(env, label);
+        self.loop_labels.pop();
+
+        stmt
+    }
+
+    fn loop_statement_body(
+        &mut self,
+        env: &EnvHandle,
+        label: Option<Id>,
+    ) -> Result<Statement, Stumble> {
+        let stmt = match self.token_kind() {
+            Some(TknK::Loop) => {
                 self.consume(&TknK::Loop);
 
                 self.consume(&TknK::BraceL);
@@ -167,32 +318,40 @@ impl TreeWalker {
 
                 self.consume(&TknK::BraceR);
 
-                stmt = Statement::mk_loop(statements);
+                Statement::mk_loop(label, statements)
             }
 
-            TknK::While => {
+            Some(TknK::While) => {
                 self.consume(&TknK::While);
                 let loop_env = Env::narrow(env.clone());
 
                 // TODO: Cosmetic parens
                 self.consume(&TknK::ParenL);
-                let condition = self.expression(&loop_env)?;
+                let condition = self.located_expression(&loop_env)?;
                 self.consume(&TknK::ParenR);
 
                 let statements = self.block_statements(&loop_env)?;
 
-                stmt = Statement::mk_while(condition, statements);
+                Statement::mk_while(label, condition, statements, None)
             }
 
-            TknK::For => {
+            Some(TknK::For) => {
                 // Desugar the for to a while loop.
                 // A little more specifically, to a block containig:
                 // - The initialiser if present
                 // - A while statement with the condition if present or a default `true` expression
-                // - With the body of the while extended with the increment statement if present.
+                // - With the increment of the while as the increment statement if present.
+                //   As the increment belongs to the while, rather than its body, a continue does not skip the increment.
 
+                let location = self.location()?;
                 self.consume(&TknK::For);
 
+                if let Some(TknK::Identifier { .. }) = self.token_kind_ahead(1)
+                    && let Some(TknK::In) = self.token_kind_ahead(2)
+                {
+                    return self.for_in(env, label, location);
+                }
+
                 let for_env = Env::narrow(env.clone());
                 let while_env = Env::narrow(for_env.clone());
 
@@ -219,83 +378,157 @@ impl TreeWalker {
 
                 self.consume(&TknK::ParenR);
 
-                let mut statements = self.block_statements(&while_env)?;
+                let statements = self.block_statements(&while_env)?;
 
-                match increment {
-                    Expr::Empty => {}
+                let increment = match increment {
+                    Expr::Empty => None,
 
-                    _ => statements.push(Statement::mk_expression(increment)),
-                }
+                    _ => Some(increment),
+                };
 
-                loop_block.push(Statement::mk_while(condition, statements));
+                loop_block.push(Statement::mk_while(label, condition, statements, increment));
 
-                stmt = Statement::mk_block(loop_block);
+                Statement::mk_block(loop_block)
             }
 
-            TknK::Function => {
-                self.consume(&TknK::Function);
+            _ => return Err(self.stumble_token(StumbleKind::ExpectedLoop)),
+        };
 
-                let id;
-                let params;
+        Ok(stmt)
+    }
 
-                match self.expression(env)? {
-                    Expr::Call { caller, args } => {
-                        id = self.to_identifier(*caller)?;
-                        params = self.to_identifiers(args)?;
-                    }
+    /// Consume a break or continue keyword and the optional label which follows.
+    ///
+    /// As a break or continue must be within a loop, `outside` is returned as an error if there is no enclosing loop.
+    fn jump_label(&mut self, outside: StumbleKind) -> Result<Option<Id>, Stumble> {
+        if self.loop_labels.is_empty() {
+            return Err(self.stumble_token(outside));
+        }
 
-                    _ => {
-                        return Err(self.stumble_token(StumbleKind::Unexpected(self.token_index)));
-                    }
-                };
+        unsafe { self.consume_unchecked() };
 
-                env.borrow_mut().insert(id.name(), ExprB::Nil);
+        match self.token_kind() {
+            Some(TknK::Identifier { id }) => {
+                let label = id.to_owned();
 
-                let lambda_env = Env::narrow(env.clone());
-                {
-                    let mut e = lambda_env.borrow_mut();
-                    for p in &params {
-                        e.insert(p.name(), ExprB::Nil);
-                    }
+                if !self.loop_labels.contains(&Some(label.clone())) {
+                    return Err(self.stumble_token(StumbleKind::UnknownLabel {
+                        label: label.to_string(),
+                    }));
                 }
 
-                let body = match self.statement(&lambda_env)? {
-                    Statement::Block { statements } => statements,
+                unsafe { self.consume_unchecked() };
 
-                    _ => return Err(self.stumble_token(StumbleKind::ExpectedBlock)),
+                Ok(Some(label))
+            }
+
+            _ => Ok(None),
+        }
+    }
+
+    /// A `for (x in iterable) { ... }` statement, from the opening paren.
+    ///
+    /// The iterable is parsed in `env`, and the body in an environment narrowed from `env` which contains the loop variable.
+    fn for_in(
+        &mut self,
+        env: &EnvHandle,
+        label: Option<Id>,
+        location: Location,
+    ) -> Result<Statement, Stumble> {
+        self.consume(&TknK::ParenL)?;
+
+        let id = match self.token_kind() {
+            Some(TknK::Identifier { id }) => id.to_owned(),
+
+            _ => return Err(self.stumble_token(StumbleKind::MissingToken)),
+        };
+        unsafe { self.consume_unchecked() };
+
+        self.consume(&TknK::In)?;
+
+        let iterable = self.located_expression(env)?;
+
+        self.consume(&TknK::ParenR)?;
+
+        let for_env = Env::narrow(env.clone());
+        let slot = for_env.borrow_mut().declare(&id);
+
+        let body = self.block_statements(&for_env)?;
+
+        Ok(Statement::mk_for_in(
+            location,
+            label,
+            Identifier::local(id, slot),
+            iterable,
+            body,
+        ))
+    }
+
+    /// A `try` statement, from the keyword, with a `catch` clause, a `finally` clause, or both.
+    fn try_statement(&mut self, env: &EnvHandle) -> Result<Statement, Stumble> {
+        self.consume(&TknK::Try);
+
+        let body = self.block_statements(&Env::narrow(env.clone()))?;
+
+        let catch = match self.token_kind() {
+            Some(TknK::Catch) => {
+                self.consume(&TknK::Catch);
+                self.consume(&TknK::ParenL)?;
+
+                let id = match self.token_kind() {
+                    Some(TknK::Identifier { id }) => id.to_owned(),
+
+                    _ => return Err(self.stumble_token(StumbleKind::MissingToken)),
                 };
+                unsafe { self.consume_unchecked() };
 
-                stmt = Statement::mk_function(id, params, body);
-            }
+                self.consume(&TknK::ParenR)?;
 
-            TknK::Semicolon => stmt = Statement::Empty,
+                let catch_env = Env::narrow(env.clone());
+                let slot = catch_env.borrow_mut().declare(&id);
 
-            TknK::Break => {
-                stmt = {
-                    self.consume(&TknK::Break);
-                    self.close_statement()?;
-                    Statement::Break
-                }
+                let statements = self.block_statements(&catch_env)?;
+
+                Some((Identifier::local(id, slot), statements))
             }
 
-            TknK::Return => {
-                self.consume(&TknK::Return);
-                let rexpr = self.expression_delimited(env, &TknK::Semicolon)?;
-                self.consume(&TknK::Semicolon);
-                stmt = Statement::Return { expr: rexpr }
+            _ => None,
+        };
+
+        let finally = match self.token_kind() {
+            Some(TknK::Finally) => {
+                self.consume(&TknK::Finally);
+                Some(self.block_statements(&Env::narrow(env.clone()))?)
             }
 
-            _ => match self.expression(env) {
-                Err(_) => todo!("Statement {:?}", self.token()),
+            _ => None,
+        };
 
-                Ok(expr) => {
-                    self.close_statement()?;
-                    stmt = Statement::mk_expression(expr);
-                }
-            },
+        if catch.is_none() && finally.is_none() {
+            return Err(self.stumble_token(StumbleKind::ExpectedCatch));
         }
 
-        Ok(stmt)
+        Ok(Statement::mk_try(body, catch, finally))
+    }
+
+    /// Whether the brace at the current token opens a map literal rather than a block.
+    ///
+    /// At the start of a statement a brace is read as a block, unless it is immediately followed by a literal key and a colon.
+    fn map_ahead(&self) -> bool {
+        let literal_key = matches!(
+            self.token_kind_ahead(1),
+            Some(
+                TknK::String { .. }
+                    | TknK::BigInteger { .. }
+                    | TknK::Integer { .. }
+                    | TknK::Number { .. }
+                    | TknK::True
+                    | TknK::False
+                    | TknK::Nil,
+            )
+        );
+
+        literal_key && matches!(self.token_kind_ahead(2), Some(TknK::Colon))
     }
 
     /// Returns an Expression on a successful parse, or an Expression::Empty on an unsuccesful parse due to an unexpected token of kind `delimiter`.
@@ -329,27 +562,98 @@ impl TreeWalker {
         self.assignment(env)
     }
 
+    /// An expression located at its first token, so runtime errors within the expression are reported at the expression.
+    fn located_expression(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
+        let location = self.location()?;
+        Ok(Expr::mk_located(location, self.expression(env)?))
+    }
+
+    /// The location of the current token.
+    fn location(&self) -> Result<Location, Stumble> {
+        match self.token() {
+            Some(token) => Ok(token.location),
+
+            None => Err(self.stumble_token(StumbleKind::TokensExhausted)),
+        }
+    }
+
     fn assignment(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
-        if let Some(TknK::Identifier { id }) = self.token_kind() {
-            if let Some(TknK::Equal) = self.token_kind_ahead(1) {
-                let offset = match env.borrow().offset(id) {
-                    Some(d) => d,
+        if let Some(TknK::Identifier { id }) = self.token_kind()
+            && let Some(TknK::Equal) = self.token_kind_ahead(1)
+        {
+            let address = match env.borrow().resolve(id) {
+                Some(address) => address,
 
-                    None => panic!("! No offset found"),
-                };
+                None => panic!("! No offset found"),
+            };
 
-                let id = Expr::mk_identifier(id.to_owned(), Some(offset));
+            let id = Expr::mk_identifier(id.to_owned(), Some(address));
 
-                unsafe { self.consume_unchecked() };
-                self.consume(&TknK::Equal);
-                let assignment = self.assignment(env)?;
-                let expr = Expr::mk_assignment(id, assignment);
+            unsafe { self.consume_unchecked() };
+            self.consume(&TknK::Equal);
+            let assignment = self.assignment(env)?;
+            let expr = Expr::mk_assignment(id, assignment);
 
-                return Ok(expr);
-            }
+            return Ok(expr);
         }
 
-        self.logic_or(env)
+        let expr = self.ternary(env)?;
+
+        let op = match self.token_kind() {
+            Some(TknK::Equal) => match expr {
+                Expr::Index { .. } => {
+                    self.consume(&TknK::Equal);
+                    let assignment = self.assignment(env)?;
+                    return Ok(Expr::mk_assignment(expr, assignment));
+                }
+
+                _ => return Err(self.stumble_token(StumbleKind::InvalidAsignee)),
+            },
+
+            Some(TknK::PlusEqual) => OpTwo::Plus,
+
+            Some(TknK::MinusEqual) => OpTwo::Minus,
+
+            Some(TknK::StarEqual) => OpTwo::Star,
+
+            Some(TknK::SlashEqual) => OpTwo::Slash,
+
+            Some(TknK::PercentEqual) => OpTwo::Percent,
+
+            _ => return Ok(expr),
+        };
+
+        let target = self.update_target(expr)?;
+        unsafe { self.consume_unchecked() };
+        let e = self.assignment(env)?;
+
+        Ok(Expr::mk_update(op, target, e, false))
+    }
+
+    /// `expr`, if `expr` may be updated in place.
+    fn update_target(&self, expr: Expr) -> Result<Expr, Stumble> {
+        match expr {
+            Expr::Identifier { .. } | Expr::Index { .. } => Ok(expr),
+
+            _ => Err(self.stumble_token(StumbleKind::InvalidAsignee)),
+        }
+    }
+
+    fn ternary(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
+        let condition = self.logic_or(env)?;
+
+        match self.token_kind() {
+            Some(TknK::Question) => {
+                self.consume(&TknK::Question);
+                let case_if = self.expression(env)?;
+                self.consume(&TknK::Colon)?;
+                let case_else = self.ternary(env)?;
+
+                Ok(Expr::mk_ternary(condition, case_if, case_else))
+            }
+
+            _ => Ok(condition),
+        }
     }
 
     fn logic_or(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
@@ -402,7 +706,7 @@ impl TreeWalker {
     }
 
     fn comparison(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
-        let mut expr = self.term(env)?;
+        let mut expr = self.range(env)?;
 
         'comparison_match: while let Some(token) = self.token() {
             match &token.kind {
@@ -433,6 +737,81 @@ impl TreeWalker {
         Ok(expr)
     }
 
+    fn range(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
+        let expr = self.bit_or(env)?;
+
+        match self.token_kind() {
+            Some(TknK::DotDot) => {
+                self.consume(&TknK::DotDot);
+                Ok(Expr::mk_range(expr, self.bit_or(env)?, false))
+            }
+
+            Some(TknK::DotDotEqual) => {
+                self.consume(&TknK::DotDotEqual);
+                Ok(Expr::mk_range(expr, self.bit_or(env)?, true))
+            }
+
+            _ => Ok(expr),
+        }
+    }
+
+    // The bitwise operators bind more tightly than comparison, with `|` loosest, then `^`, `&`, and shifts.
+
+    fn bit_or(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
+        let mut expr = self.bit_xor(env)?;
+
+        while let Some(TknK::Pipe) = self.token_kind() {
+            self.consume(&TknK::Pipe);
+            expr = Expr::mk_binary(OpTwo::Pipe, expr, self.bit_xor(env)?);
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_xor(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
+        let mut expr = self.bit_and(env)?;
+
+        while let Some(TknK::Caret) = self.token_kind() {
+            self.consume(&TknK::Caret);
+            expr = Expr::mk_binary(OpTwo::Caret, expr, self.bit_and(env)?);
+        }
+
+        Ok(expr)
+    }
+
+    fn bit_and(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
+        let mut expr = self.shift(env)?;
+
+        while let Some(TknK::Ampersand) = self.token_kind() {
+            self.consume(&TknK::Ampersand);
+            expr = Expr::mk_binary(OpTwo::Ampersand, expr, self.shift(env)?);
+        }
+
+        Ok(expr)
+    }
+
+    fn shift(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
+        let mut expr = self.term(env)?;
+
+        while let Some(token) = self.token() {
+            match &token.kind {
+                TknK::LessLess => {
+                    self.consume(&TknK::LessLess);
+                    expr = Expr::mk_binary(OpTwo::LessLess, expr, self.term(env)?)
+                }
+
+                TknK::GreaterGreater => {
+                    self.consume(&TknK::GreaterGreater);
+                    expr = Expr::mk_binary(OpTwo::GreaterGreater, expr, self.term(env)?)
+                }
+
+                _ => break,
+            }
+        }
+
+        Ok(expr)
+    }
+
     fn term(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
         let mut expr = self.factor(env)?;
 
@@ -440,12 +819,12 @@ impl TreeWalker {
             match &token.kind {
                 TknK::Minus => {
                     self.consume(&TknK::Minus);
-                    expr = Expr::mk_binary(OpTwo::Minus, expr, self.term(env)?)
+                    expr = Expr::mk_binary(OpTwo::Minus, expr, self.factor(env)?)
                 }
 
                 TknK::Plus => {
                     self.consume(&TknK::Plus);
-                    expr = Expr::mk_binary(OpTwo::Plus, expr, self.term(env)?)
+                    expr = Expr::mk_binary(OpTwo::Plus, expr, self.factor(env)?)
                 }
 
                 _ => break,
@@ -461,12 +840,22 @@ impl TreeWalker {
             match &token.kind {
                 TknK::Slash => {
                     self.consume(&TknK::Slash);
-                    expr = Expr::mk_binary(OpTwo::Slash, expr, self.factor(env)?)
+                    expr = Expr::mk_binary(OpTwo::Slash, expr, self.unary(env)?)
                 }
 
                 TknK::Star => {
                     self.consume(&TknK::Star);
-                    expr = Expr::mk_binary(OpTwo::Star, expr, self.factor(env)?)
+                    expr = Expr::mk_binary(OpTwo::Star, expr, self.unary(env)?)
+                }
+
+                TknK::Percent => {
+                    self.consume(&TknK::Percent);
+                    expr = Expr::mk_binary(OpTwo::Percent, expr, self.unary(env)?)
+                }
+
+                TknK::TildeSlash => {
+                    self.consume(&TknK::TildeSlash);
+                    expr = Expr::mk_binary(OpTwo::TildeSlash, expr, self.unary(env)?)
                 }
 
                 _ => break,
@@ -491,7 +880,26 @@ impl TreeWalker {
                         Expr::mk_unary(OpOne::Minus, self.unary(env)?)
                     }
 
-                    _ => self.call(env)?,
+                    TknK::Tilde => {
+                        self.consume(&TknK::Tilde);
+                        Expr::mk_unary(OpOne::Tilde, self.unary(env)?)
+                    }
+
+                    TknK::PlusPlus => {
+                        self.consume(&TknK::PlusPlus);
+                        let target = self.unary(env)?;
+                        let target = self.update_target(target)?;
+                        Expr::mk_update(OpTwo::Plus, target, Expr::mk_integer(1), false)
+                    }
+
+                    TknK::MinusMinus => {
+                        self.consume(&TknK::MinusMinus);
+                        let target = self.unary(env)?;
+                        let target = self.update_target(target)?;
+                        Expr::mk_update(OpTwo::Minus, target, Expr::mk_integer(1), false)
+                    }
+
+                    _ => self.power(env)?,
                 };
 
                 Ok(expr)
@@ -499,6 +907,21 @@ impl TreeWalker {
         }
     }
 
+    // Exponentiation binds more tightly than a unary operator to the left, and is right associative.
+    // So, `-2 ** 2` is `-(2 ** 2)`, `2 ** -1` is `2 ** (-1)`, and `2 ** 3 ** 2` is `2 ** (3 ** 2)`.
+    fn power(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
+        let expr = self.call(env)?;
+
+        match self.token_kind() {
+            Some(TknK::StarStar) => {
+                self.consume(&TknK::StarStar);
+                Ok(Expr::mk_binary(OpTwo::StarStar, expr, self.unary(env)?))
+            }
+
+            _ => Ok(expr),
+        }
+    }
+
     #[allow(clippy::while_let_loop)]
     fn call(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
         let mut expr = self.primary(env)?;
@@ -506,6 +929,7 @@ impl TreeWalker {
         loop {
             match self.token_kind() {
                 Some(TknK::ParenL) => {
+                    let location = self.location()?;
                     self.consume(&TknK::ParenL);
                     let mut args = Vec::default();
                     while self.token_kind().is_some_and(|kind| *kind != TknK::ParenR) {
@@ -521,14 +945,45 @@ impl TreeWalker {
 
                     self.consume(&TknK::ParenR);
 
-                    expr = Expr::mk_call(expr, args);
+                    expr = Expr::mk_call(expr, args, location);
+                }
+
+                Some(TknK::BracketL) => {
+                    self.consume(&TknK::BracketL);
+                    let index = self.expression(env)?;
+                    self.consume(&TknK::BracketR)?;
+
+                    expr = Expr::mk_index(expr, index);
+                }
+
+                Some(TknK::Dot) => {
+                    self.consume(&TknK::Dot);
+                    let name = match self.token_kind() {
+                        Some(TknK::Identifier { id }) => id.to_owned(),
+
+                        _ => return Err(self.stumble_token(StumbleKind::ExpectedProperty)),
+                    };
+                    unsafe { self.consume_unchecked() };
+
+                    expr = Expr::mk_get(expr, name);
                 }
 
                 _ => break,
             }
         }
 
-        Ok(expr)
+        let op = match self.token_kind() {
+            Some(TknK::PlusPlus) => OpTwo::Plus,
+
+            Some(TknK::MinusMinus) => OpTwo::Minus,
+
+            _ => return Ok(expr),
+        };
+
+        let target = self.update_target(expr)?;
+        unsafe { self.consume_unchecked() };
+
+        Ok(Expr::mk_update(op, target, Expr::mk_integer(1), true))
     }
 
     fn primary(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
@@ -537,10 +992,18 @@ impl TreeWalker {
 
             Some(token) => {
                 let expr = match &token.kind {
+                    TknK::BigInteger { literal } => {
+                        Expr::Basic(ExprB::BigInteger { i: literal.clone() })
+                    }
+
+                    TknK::Integer { literal } => Expr::mk_integer(*literal),
+
                     TknK::Number { literal } => Expr::mk_numeric(*literal),
 
                     TknK::String { literal } => Expr::mk_string(literal.to_owned()),
 
+                    TknK::Interpolation { .. } => return self.interpolation(env),
+
                     TknK::True => Expr::mk_true(),
 
                     TknK::False => Expr::mk_false(),
@@ -548,7 +1011,7 @@ impl TreeWalker {
                     TknK::Nil => Expr::mk_nil(),
 
                     TknK::Identifier { id } => {
-                        Expr::mk_identifier(id.to_owned(), env.borrow().offset(id))
+                        Expr::mk_identifier(id.to_owned(), env.borrow().resolve(id))
                     }
 
                     TknK::ParenL => {
@@ -559,6 +1022,51 @@ impl TreeWalker {
                         expr
                     }
 
+                    TknK::BracketL => {
+                        self.consume(&TknK::BracketL);
+
+                        let mut elements = Vec::default();
+                        while self
+                            .token_kind()
+                            .is_some_and(|kind| *kind != TknK::BracketR)
+                        {
+                            elements.push(self.expression(env)?);
+
+                            match self.token_kind() {
+                                Some(TknK::Comma) => self.consume(&TknK::Comma)?,
+
+                                _ => break,
+                            }
+                        }
+
+                        self.check_token(&TknK::BracketR)?;
+
+                        Expr::mk_list(elements)
+                    }
+
+                    TknK::BraceL => {
+                        self.consume(&TknK::BraceL);
+
+                        let mut entries = Vec::default();
+                        while self.token_kind().is_some_and(|kind| *kind != TknK::BraceR) {
+                            let key = self.expression(env)?;
+                            self.consume(&TknK::Colon)?;
+                            let value = self.expression(env)?;
+
+                            entries.push((key, value));
+
+                            match self.token_kind() {
+                                Some(TknK::Comma) => self.consume(&TknK::Comma)?,
+
+                                _ => break,
+                            }
+                        }
+
+                        self.check_token(&TknK::BraceR)?;
+
+                        Expr::mk_map(entries)
+                    }
+
                     _ => {
                         return Err(self.stumble_token(StumbleKind::Unexpected(self.token_index)));
                     }
@@ -569,4 +1077,33 @@ impl TreeWalker {
             }
         }
     }
+
+    /// An interpolated string, from the first segment of the string.
+    ///
+    /// Each interpolation token is a segment followed by an expression, and the string ends with a string token.
+    /// Each expression is located, so runtime errors within the expression are reported at the expression.
+    fn interpolation(&mut self, env: &EnvHandle) -> Result<Expr, Stumble> {
+        let mut parts = Vec::default();
+
+        loop {
+            let (literal, ends) = match self.token_kind() {
+                Some(TknK::Interpolation { literal }) => (literal.to_owned(), false),
+
+                Some(TknK::String { literal }) => (literal.to_owned(), true),
+
+                _ => return Err(self.stumble_token(StumbleKind::Unexpected(self.token_index))),
+            };
+            unsafe { self.consume_unchecked() };
+
+            if !literal.is_empty() {
+                parts.push(Expr::mk_string(literal));
+            }
+
+            if ends {
+                return Ok(Expr::mk_interpolation(parts));
+            }
+
+            parts.push(self.located_expression(env)?);
+        }
+    }
 }
@@ -1,4 +1,10 @@
-use crate::interpreter::{TreeWalker, environment::Env};
+use std::rc::Rc;
+
+use crate::interpreter::{
+    TreeWalker,
+    ast::expression::{Expr, ExprB},
+    environment::Env,
+};
 
 #[test]
 fn simple() {
@@ -7,7 +13,7 @@ fn simple() {
     let mut parser = TreeWalker::default();
     parser.scan(input);
 
-    let env = Env::fresh_std_env();
+    let env = parser.fresh_std_env();
     let expr = parser.expression(&env);
 
     assert!(expr.is_ok());
@@ -24,7 +30,7 @@ fn arithmetic() {
     let mut parser = TreeWalker::default();
     parser.scan(input);
 
-    let env = Env::fresh_std_env();
+    let env = parser.fresh_std_env();
 
     let expr = parser.expression(&env);
 
@@ -32,7 +38,7 @@ fn arithmetic() {
 
     let expr = expr.unwrap();
 
-    assert_eq!(format!("{expr}"), "(/ 4 (* 3 (- 2)))");
+    assert_eq!(format!("{expr}"), "(* (/ 4 3) (- 2))");
 }
 
 #[test]
@@ -42,7 +48,7 @@ fn sync() {
     let mut parser = TreeWalker::default();
     parser.scan(input);
 
-    let env = Env::fresh_std_env();
+    let env = parser.fresh_std_env();
 
     let expr = parser.expression(&env);
 
@@ -62,3 +68,85 @@ fn sync() {
         }
     }
 }
+
+#[test]
+fn ternary() {
+    let input = "a or b ? 1 : c ? 2 : 3";
+
+    let mut parser = TreeWalker::default();
+    parser.scan(input);
+
+    let env = parser.fresh_std_env();
+
+    let expr = parser.expression(&env).expect("Parse error");
+
+    assert_eq!(format!("{expr}"), "(? (OR a[-] b[-]) 1 (? c[-] 2 3))");
+}
+
+#[test]
+fn precedence() {
+    let cases = [
+        ("8 - 2 - 1", "(- (- 8 2) 1)"),
+        ("7 ~/ 2 * 3 % 4", "(% (* (~/ 7 2) 3) 4)"),
+        ("1 + 2 % 3", "(+ 1 (% 2 3))"),
+        ("2 ** 3 ** 2", "(** 2 (** 3 2))"),
+        ("-2 ** 2", "(- (** 2 2))"),
+        ("2 ** -1", "(** 2 (- 1))"),
+        ("2 * 3 ** 2", "(* 2 (** 3 2))"),
+        ("1 | 2 ^ 3 & 4 << 1 + 1", "(| 1 (^ 2 (& 3 (<< 4 (+ 1 1)))))"),
+        ("1 < 2 | 3", "(< 1 (| 2 3))"),
+        ("~1 >> 2", "(>> (~ 1) 2)"),
+        ("-s.len() + 1", "(+ (- s[-].len()) 1)"),
+        ("a.b(1)[2].c", "a[-].b(1)[2].c"),
+    ];
+
+    for (input, expected) in cases {
+        let mut parser = TreeWalker::default();
+        parser.scan(input);
+
+        let env = parser.fresh_std_env();
+
+        let expr = parser.expression(&env).expect("Parse error");
+
+        assert_eq!(format!("{expr}"), expected);
+    }
+}
+
+#[test]
+fn resolution() {
+    let mut parser = TreeWalker::default();
+    parser.scan("a + b * c");
+
+    let outer = parser.fresh_std_env();
+    outer.borrow_mut().declare(&parser.interner.symbol("a"));
+    outer.borrow_mut().declare(&parser.interner.symbol("b"));
+
+    let inner = Env::narrow(outer);
+    inner.borrow_mut().declare(&parser.interner.symbol("c"));
+    inner.borrow_mut().declare(&parser.interner.symbol("a"));
+
+    let expr = parser.expression(&inner).expect("Parse error");
+
+    assert_eq!(format!("{expr}"), "(+ a[0:1] (* b[1:1] c[0:0]))");
+}
+
+#[test]
+fn interned() {
+    let mut parser = TreeWalker::default();
+    parser.scan(r#"["ab", "ab"]"#);
+
+    let env = parser.fresh_std_env();
+    let expr = parser.expression(&env).expect("Parse error");
+
+    let Expr::List { elements } = expr else {
+        panic!("Expected a list");
+    };
+
+    match (&elements[0], &elements[1]) {
+        (Expr::Basic(ExprB::String { s: a }), Expr::Basic(ExprB::String { s: b })) => {
+            assert!(Rc::ptr_eq(a, b))
+        }
+
+        _ => panic!("Expected strings"),
+    }
+}
@@ -1,6 +1,9 @@
 use std::{iter::Peekable, str::Chars};
 
-use crate::interpreter::scanner::token::{Tkn, TknK};
+use crate::interpreter::{
+    ast::expression::BigInt,
+    scanner::token::{Tkn, TknK},
+};
 
 use super::{
     TreeWalker,
@@ -31,6 +34,11 @@ impl TreeWalker {
             }
         }
 
+        if !self.interpolations.is_empty() {
+            self.interpolations.clear();
+            return Err(self.stumble_here(StumbleKind::UnterminatedInterpolation));
+        }
+
         // std::mem::replace(&mut self.source, held_source);
 
         Ok(())
@@ -52,18 +60,21 @@ impl TreeWalker {
             Some(c) => {
                 match c {
                     '"' => {
-                        let literal = self.get_string(chars)?;
-                        let advance = literal.len() + 2;
-
-                        self.store_token(TknK::String { literal }, advance);
+                        let (token_kind, advance) = self.get_string(chars)?;
+                        self.store_token(token_kind, advance);
                     }
 
                     '/' => {
                         chars.next();
-                        if let Some('/') = chars.peek() {
-                            self.eat_until('\n', chars);
-                        } else {
-                            self.store_token(TknK::Slash, 1);
+                        match chars.peek() {
+                            Some('/') => self.eat_until('\n', chars),
+
+                            Some('=') => {
+                                chars.next();
+                                self.store_token(TknK::SlashEqual, 2);
+                            }
+
+                            _ => self.store_token(TknK::Slash, 1),
                         }
                     }
 
@@ -78,15 +89,51 @@ impl TreeWalker {
                     }
 
                     '{' => {
+                        if let Some(depth) = self.interpolations.last_mut() {
+                            *depth += 1;
+                        }
+
                         chars.next();
                         self.store_token(TknK::BraceL, 1);
                     }
 
+                    // A brace which closes an interpolation continues the string.
+                    '}' if self.interpolations.last() == Some(&0) => {
+                        self.interpolations.pop();
+
+                        let (token_kind, advance) = self.get_string(chars)?;
+                        self.store_token(token_kind, advance);
+                    }
+
                     '}' => {
+                        if let Some(depth) = self.interpolations.last_mut() {
+                            *depth -= 1;
+                        }
+
                         chars.next();
                         self.store_token(TknK::BraceR, 1);
                     }
 
+                    '[' => {
+                        chars.next();
+                        self.store_token(TknK::BracketL, 1);
+                    }
+
+                    ']' => {
+                        chars.next();
+                        self.store_token(TknK::BracketR, 1);
+                    }
+
+                    '?' => {
+                        chars.next();
+                        self.store_token(TknK::Question, 1);
+                    }
+
+                    ':' => {
+                        chars.next();
+                        self.store_token(TknK::Colon, 1);
+                    }
+
                     ',' => {
                         chars.next();
                         self.store_token(TknK::Comma, 1);
@@ -94,17 +141,61 @@ impl TreeWalker {
 
                     '.' => {
                         chars.next();
-                        self.store_token(TknK::Dot, 1);
+                        if let Some('.') = chars.peek() {
+                            chars.next();
+                            if let Some('=') = chars.peek() {
+                                chars.next();
+                                self.store_token(TknK::DotDotEqual, 3);
+                            } else {
+                                self.store_token(TknK::DotDot, 2);
+                            }
+                        } else {
+                            self.store_token(TknK::Dot, 1);
+                        }
                     }
 
                     '-' => {
                         chars.next();
-                        self.store_token(TknK::Minus, 1);
+                        match chars.peek() {
+                            Some('=') => {
+                                chars.next();
+                                self.store_token(TknK::MinusEqual, 2);
+                            }
+
+                            Some('-') => {
+                                chars.next();
+                                self.store_token(TknK::MinusMinus, 2);
+                            }
+
+                            _ => self.store_token(TknK::Minus, 1),
+                        }
                     }
 
                     '+' => {
                         chars.next();
-                        self.store_token(TknK::Plus, 1);
+                        match chars.peek() {
+                            Some('=') => {
+                                chars.next();
+                                self.store_token(TknK::PlusEqual, 2);
+                            }
+
+                            Some('+') => {
+                                chars.next();
+                                self.store_token(TknK::PlusPlus, 2);
+                            }
+
+                            _ => self.store_token(TknK::Plus, 1),
+                        }
+                    }
+
+                    '%' => {
+                        chars.next();
+                        if let Some('=') = chars.peek() {
+                            chars.next();
+                            self.store_token(TknK::PercentEqual, 2);
+                        } else {
+                            self.store_token(TknK::Percent, 1);
+                        }
                     }
 
                     ';' => {
@@ -114,7 +205,30 @@ impl TreeWalker {
 
                     '*' => {
                         chars.next();
-                        self.store_token(TknK::Star, 1);
+                        match chars.peek() {
+                            Some('=') => {
+                                chars.next();
+                                self.store_token(TknK::StarEqual, 2);
+                            }
+
+                            Some('*') => {
+                                chars.next();
+                                self.store_token(TknK::StarStar, 2);
+                            }
+
+                            _ => self.store_token(TknK::Star, 1),
+                        }
+                    }
+
+                    // As `//` begins a comment, integer division is written `~/`.
+                    '~' => {
+                        chars.next();
+                        if let Some('/') = chars.peek() {
+                            chars.next();
+                            self.store_token(TknK::TildeSlash, 2);
+                        } else {
+                            self.store_token(TknK::Tilde, 1);
+                        }
                     }
 
                     '!' => {
@@ -139,27 +253,56 @@ impl TreeWalker {
 
                     '<' => {
                         chars.next();
-                        if let Some('=') = chars.peek() {
-                            chars.next();
-                            self.store_token(TknK::LessEqual, 2);
-                        } else {
-                            self.store_token(TknK::Less, 1);
+                        match chars.peek() {
+                            Some('=') => {
+                                chars.next();
+                                self.store_token(TknK::LessEqual, 2);
+                            }
+
+                            Some('<') => {
+                                chars.next();
+                                self.store_token(TknK::LessLess, 2);
+                            }
+
+                            _ => self.store_token(TknK::Less, 1),
                         }
                     }
 
                     '>' => {
                         chars.next();
-                        if let Some('=') = chars.peek() {
-                            chars.next();
-                            self.store_token(TknK::GreaterEqual, 2);
-                        } else {
-                            self.store_token(TknK::Greater, 1);
+                        match chars.peek() {
+                            Some('=') => {
+                                chars.next();
+                                self.store_token(TknK::GreaterEqual, 2);
+                            }
+
+                            Some('>') => {
+                                chars.next();
+                                self.store_token(TknK::GreaterGreater, 2);
+                            }
+
+                            _ => self.store_token(TknK::Greater, 1),
                         }
                     }
 
-                    numeric if numeric.is_numeric() => {
-                        let (number, chars) = self.get_f64(chars)?;
-                        self.store_token(TknK::Number { literal: number }, chars);
+                    '&' => {
+                        chars.next();
+                        self.store_token(TknK::Ampersand, 1);
+                    }
+
+                    '|' => {
+                        chars.next();
+                        self.store_token(TknK::Pipe, 1);
+                    }
+
+                    '^' => {
+                        chars.next();
+                        self.store_token(TknK::Caret, 1);
+                    }
+
+                    numeric if numeric.is_ascii_digit() => {
+                        let (token_kind, advance) = self.get_number(chars)?;
+                        self.store_token(token_kind, advance);
                     }
 
                     alphabetic if alphabetic.is_alphabetic() => {
@@ -203,28 +346,69 @@ impl TreeWalker {
         }
     }
 
-    // Consume tokens until a (closing) `"` is found and return the enclosed string.
-    fn get_string(&mut self, chars: &mut Peekable<Chars<'_>>) -> Result<String, Stumble> {
+    // Consume tokens from an opening `"`, or the `}` closing an interpolation, until a closing `"` or an opening `${`.
+    // Returns either a string or an interpolation token, together with the number of characters consumed.
+    //
+    // Within a string `\$`, `\"`, `\\`, `\n`, and `\t` are escapes, and any other backslash is kept as written.
+    fn get_string(&mut self, chars: &mut Peekable<Chars<'_>>) -> Result<(TknK, usize), Stumble> {
         chars.next();
+        let mut advance = 1;
         let mut literal = String::default();
 
-        while let Some(d) = chars.peek() {
+        while let Some(&d) = chars.peek() {
             match d {
                 '"' => {
                     chars.next();
+                    advance += 1;
                     break;
                 }
 
+                '$' if chars.clone().nth(1) == Some('{') => {
+                    chars.nth(1);
+                    advance += 2;
+
+                    self.interpolations.push(0);
+                    let literal = self.interner.intern(&literal);
+                    return Ok((TknK::Interpolation { literal }, advance));
+                }
+
+                '\\' => {
+                    let escaped = match chars.clone().nth(1) {
+                        Some('$') => Some('$'),
+                        Some('"') => Some('"'),
+                        Some('\\') => Some('\\'),
+                        Some('n') => Some('\n'),
+                        Some('t') => Some('\t'),
+                        _ => None,
+                    };
+
+                    match escaped {
+                        Some(e) => {
+                            chars.nth(1);
+                            advance += 2;
+                            literal.push(e);
+                        }
+
+                        None => {
+                            chars.next();
+                            advance += 1;
+                            literal.push(d);
+                        }
+                    }
+                }
+
                 '\n' => return Err(self.stumble_here(StumbleKind::MultilineString)),
 
                 _ => {
-                    literal.push(*d);
+                    literal.push(d);
                     chars.next();
+                    advance += 1;
                 }
             }
         }
 
-        Ok(literal)
+        let literal = self.interner.intern(&literal);
+        Ok((TknK::String { literal }, advance))
     }
 
     // Eat until a `c` is found or all tokens have been consumed.
@@ -236,13 +420,40 @@ impl TreeWalker {
         self.parse_location.newline();
     }
 
-    // Consume numeric tokens until and f64 is identified.
-    fn get_f64(&mut self, chars: &mut Peekable<Chars<'_>>) -> Result<(f64, usize), Stumble> {
+    // Consume numeric tokens until a number is identified.
+    // The number is a float if written with a fractional part or an exponent, and otherwise an integer.
+    // Integers may also be written in hexadecimal, following `0x`.
+    fn get_number(&mut self, chars: &mut Peekable<Chars<'_>>) -> Result<(TknK, usize), Stumble> {
+        let mut ahead = chars.clone();
+        if let (Some('0'), Some('x' | 'X'), Some(h)) = (ahead.next(), ahead.next(), ahead.next())
+            && h.is_ascii_hexdigit()
+        {
+            chars.nth(1);
+
+            let mut hex = String::default();
+            while let Some(&h) = chars.peek()
+                && h.is_ascii_hexdigit()
+            {
+                hex.push(h);
+                chars.next();
+            }
+
+            let literal = BigInt::from_str_radix(&hex, 16).unwrap_or_default();
+            return Ok((integer_token(literal), hex.len() + 2));
+        }
+
         let mut number = String::default();
+        let mut float = false;
+
+        while let Some(&c) = chars.peek() {
+            // A dot followed by a dot begins a range, rather than continuing the number.
+            if c == '.' && chars.clone().nth(1) == Some('.') {
+                break;
+            }
 
-        while let Some(c) = chars.peek() {
-            if c.is_numeric() || *c == '.' {
-                number.push(*c);
+            if c.is_ascii_digit() || c == '.' {
+                float |= c == '.';
+                number.push(c);
                 chars.next();
             } else {
                 break;
@@ -253,17 +464,50 @@ impl TreeWalker {
             return Err(self.stumble_here(StumbleKind::TrailingDot));
         }
 
-        Ok((number.parse().unwrap(), number.len()))
+        // An exponent is only consumed if digits follow, so `2e` is the number `2` followed by the identifier `e`.
+        if let Some(&e @ ('e' | 'E')) = chars.peek() {
+            let mut ahead = chars.clone();
+            let mut exponent = String::from(e);
+            ahead.next();
+
+            if let Some(&sign @ ('+' | '-')) = ahead.peek() {
+                exponent.push(sign);
+                ahead.next();
+            }
+
+            if ahead.peek().is_some_and(char::is_ascii_digit) {
+                while let Some(&d) = ahead.peek()
+                    && d.is_ascii_digit()
+                {
+                    exponent.push(d);
+                    ahead.next();
+                }
+
+                *chars = ahead;
+                number.push_str(&exponent);
+                float = true;
+            }
+        }
+
+        let kind = match float {
+            true => TknK::Number {
+                literal: number.parse().unwrap(),
+            },
+
+            false => integer_token(BigInt::from_str_radix(&number, 10).unwrap_or_default()),
+        };
+
+        Ok((kind, number.len()))
     }
 
-    // Consume alphabetic tokens and return either a keyword or identifier.
+    // Consume alphabetic tokens, and following the first any digits, and return either a keyword or identifier.
     fn get_keyword_or_identifier(
         &mut self,
         chars: &mut Peekable<Chars<'_>>,
     ) -> Result<(TknK, usize), Stumble> {
         let mut alphabetic = String::default();
         while let Some(b) = chars.peek() {
-            if b.is_alphabetic() || *b == '_' {
+            if b.is_alphabetic() || b.is_ascii_digit() || *b == '_' {
                 alphabetic.push(*b);
                 chars.next();
             } else {
@@ -276,18 +520,26 @@ impl TreeWalker {
 
             "break" => TknK::Break,
 
+            "catch" => TknK::Catch,
+
             "class" => TknK::Class,
 
+            "continue" => TknK::Continue,
+
             "else" => TknK::Else,
 
             "false" => TknK::False,
 
+            "finally" => TknK::Finally,
+
             "for" => TknK::For,
 
             "fun" => TknK::Function,
 
             "if" => TknK::If,
 
+            "in" => TknK::In,
+
             "loop" => TknK::Loop,
 
             "nil" => TknK::Nil,
@@ -302,17 +554,30 @@ impl TreeWalker {
 
             "this" => TknK::This,
 
+            "throw" => TknK::Throw,
+
             "true" => TknK::True,
 
+            "try" => TknK::Try,
+
             "var" => TknK::Var,
 
             "while" => TknK::While,
 
             non_keyword => TknK::Identifier {
-                id: non_keyword.to_owned(),
+                id: self.interner.symbol(non_keyword),
             },
         };
 
         Ok((instance, alphabetic.len()))
     }
 }
+
+// An integer token, holding an i64 if the integer is within range.
+fn integer_token(literal: BigInt) -> TknK {
+    match literal.to_i64() {
+        Some(literal) => TknK::Integer { literal },
+
+        None => TknK::BigInteger { literal },
+    }
+}
@@ -1,5 +1,7 @@
 use crate::interpreter::{
     TreeWalker,
+    ast::expression::BigInt,
+    err::StumbleKind,
     location::Location,
     scanner::token::{Tkn, TknK},
 };
@@ -13,7 +15,7 @@ fn scanner_basic_numeric() {
         scanner.tokens,
         vec![
             Tkn {
-                kind: TknK::Number { literal: 1.0 },
+                kind: TknK::Integer { literal: 1 },
                 location: Location::default()
             },
             Tkn {
@@ -40,7 +42,7 @@ fn scanner_basic_keyword() {
         vec![
             Tkn {
                 kind: TknK::Identifier {
-                    id: "not".to_string()
+                    id: walker.interner.symbol("not")
                 },
                 location: Location::default()
             },
@@ -58,7 +60,7 @@ fn scanner_basic_keyword() {
             },
             Tkn {
                 kind: TknK::Identifier {
-                    id: "perhaps".to_string()
+                    id: walker.interner.symbol("perhaps")
                 },
                 location: Location::new(0, 14)
             },
@@ -73,3 +75,135 @@ fn scanner_basic_keyword() {
         ]
     );
 }
+
+#[test]
+fn scanner_range() {
+    let mut walker = TreeWalker::default();
+    walker.scan("0..10 1.5..=2");
+
+    let kinds = walker
+        .tokens
+        .into_iter()
+        .map(|t| t.kind)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        kinds,
+        vec![
+            TknK::Integer { literal: 0 },
+            TknK::DotDot,
+            TknK::Integer { literal: 10 },
+            TknK::Number { literal: 1.5 },
+            TknK::DotDotEqual,
+            TknK::Integer { literal: 2 },
+        ]
+    );
+}
+
+#[test]
+fn scanner_exponent() {
+    let mut walker = TreeWalker::default();
+    walker.scan("1e3 2.5E-2 2e");
+
+    let kinds = walker
+        .tokens
+        .into_iter()
+        .map(|t| t.kind)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        kinds,
+        vec![
+            TknK::Number { literal: 1000.0 },
+            TknK::Number { literal: 0.025 },
+            TknK::Integer { literal: 2 },
+            TknK::Identifier {
+                id: walker.interner.symbol("e")
+            },
+        ]
+    );
+}
+
+#[test]
+fn scanner_big_integer() {
+    let mut walker = TreeWalker::default();
+    walker.scan("9223372036854775807 9223372036854775808 0x1F 0xffffffffffffffff 0x");
+
+    let kinds = walker
+        .tokens
+        .into_iter()
+        .map(|t| t.kind)
+        .collect::<Vec<_>>();
+
+    let big = |digits| BigInt::from_str_radix(digits, 10).unwrap();
+
+    assert_eq!(
+        kinds,
+        vec![
+            TknK::Integer { literal: i64::MAX },
+            TknK::BigInteger {
+                literal: big("9223372036854775808")
+            },
+            TknK::Integer { literal: 31 },
+            TknK::BigInteger {
+                literal: big("18446744073709551615")
+            },
+            TknK::Integer { literal: 0 },
+            TknK::Identifier {
+                id: walker.interner.symbol("x")
+            },
+        ]
+    );
+}
+
+#[test]
+fn scanner_interpolation() {
+    let mut walker = TreeWalker::default();
+    walker.scan(r#""a${b}c${ {} }""#).unwrap();
+
+    assert_eq!(
+        walker.tokens,
+        vec![
+            Tkn {
+                kind: TknK::Interpolation {
+                    literal: "a".into()
+                },
+                location: Location::new(0, 0)
+            },
+            Tkn {
+                kind: TknK::Identifier {
+                    id: walker.interner.symbol("b")
+                },
+                location: Location::new(0, 4)
+            },
+            Tkn {
+                kind: TknK::Interpolation {
+                    literal: "c".into()
+                },
+                location: Location::new(0, 5)
+            },
+            Tkn {
+                kind: TknK::BraceL,
+                location: Location::new(0, 10)
+            },
+            Tkn {
+                kind: TknK::BraceR,
+                location: Location::new(0, 11)
+            },
+            Tkn {
+                kind: TknK::String { literal: "".into() },
+                location: Location::new(0, 13)
+            },
+        ]
+    );
+}
+
+#[test]
+fn scanner_unterminated_interpolation() {
+    let mut walker = TreeWalker::default();
+
+    assert_eq!(
+        walker.scan(r#""a ${1 + 2""#).map_err(|e| e.kind),
+        Err(StumbleKind::UnterminatedInterpolation)
+    );
+}
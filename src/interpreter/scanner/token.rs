@@ -1,4 +1,9 @@
-use crate::interpreter::location::Location;
+use std::rc::Rc;
+
+use crate::interpreter::{
+    ast::{expression::BigInt, identifier::Id},
+    location::Location,
+};
 
 pub type Tkns = Vec<Tkn>;
 
@@ -12,42 +17,84 @@ pub struct Tkn {
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub enum TknK {
     // Single character
+    Ampersand,
     BraceL,
     BraceR,
+    BracketL,
+    BracketR,
+    Caret,
+    Colon,
     Comma,
-    Dot,
     Minus,
     ParenL,
     ParenR,
+    Percent,
+    Pipe,
     Plus,
+    Question,
     Semicolon,
     Slash,
     Star,
+    Tilde,
 
     // One or two character
     Bang,
     BangEqual,
+    Dot,
+    DotDot,
+    DotDotEqual,
     Equal,
     EqualEqual,
     Greater,
     GreaterEqual,
+    GreaterGreater,
     Less,
     LessEqual,
+    LessLess,
+    MinusEqual,
+    MinusMinus,
+    PercentEqual,
+    PlusEqual,
+    PlusPlus,
+    SlashEqual,
+    StarEqual,
+    StarStar,
+    TildeSlash,
 
     // Literals
-    Identifier { id: String },
-    Number { literal: f64 },
-    String { literal: String },
+    BigInteger {
+        literal: BigInt,
+    },
+    Identifier {
+        id: Id,
+    },
+    Integer {
+        literal: i64,
+    },
+    /// A segment of a string which is followed by an interpolated expression.
+    Interpolation {
+        literal: Rc<str>,
+    },
+    Number {
+        literal: f64,
+    },
+    String {
+        literal: Rc<str>,
+    },
 
     // Keywords
     And,
     Break,
+    Catch,
     Class,
+    Continue,
     Else,
     False,
+    Finally,
     For,
     Function,
     If,
+    In,
     Loop,
     Nil,
     Or,
@@ -55,7 +102,9 @@ pub enum TknK {
     Return,
     Super,
     This,
+    Throw,
     True,
+    Try,
     Var,
     While,
 
@@ -79,7 +128,10 @@ impl Tkn {
 impl std::fmt::Display for Tkn {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.kind {
+            TknK::BigInteger { literal } => write!(f, "Integer: {literal}"),
+            TknK::Integer { literal } => write!(f, "Integer: {literal}"),
             TknK::Number { literal } => write!(f, "Number: {literal}"),
+            TknK::Interpolation { literal } => write!(f, "Interpolation: {literal}",),
             TknK::String { literal } => write!(f, "String: {literal}",),
             _ => write!(f, "Non-literal: {:?}", self.kind),
         }
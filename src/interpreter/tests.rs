@@ -1,9 +1,18 @@
 use std::io::BufWriter;
 
-use crate::interpreter::{Base, TreeWalker};
+use crate::interpreter::{
+    Base, TreeWalker,
+    config::Config,
+    err::{Stumble, StumbleKind},
+    location::Location,
+};
 
 fn test_io(input: &str, output: &str) {
-    let mut walker = TreeWalker::default();
+    test_io_with(Config::default(), input, output)
+}
+
+fn test_io_with(config: Config, input: &str, output: &str) {
+    let mut walker = TreeWalker::with_config(config);
 
     walker.scan(input);
 
@@ -32,6 +41,36 @@ fn test_io(input: &str, output: &str) {
     assert_eq!(buffer_string.expect("Failed to interpret").trim(), output);
 }
 
+fn test_stumble(input: &str) -> StumbleKind {
+    stumble(input).kind
+}
+
+fn stumble(input: &str) -> Stumble {
+    stumble_with(Config::default(), input)
+}
+
+fn stumble_with(config: Config, input: &str) -> Stumble {
+    let mut walker = TreeWalker::with_config(config);
+
+    walker.scan(input);
+
+    match walker.parse() {
+        Ok(_) => {}
+
+        Err(e) => return e,
+    };
+
+    let mut buffer = Vec::default();
+    let mut system = Base::default();
+    system.set_out(&mut buffer);
+
+    match walker.interpret_all(&mut system) {
+        Ok(_) => panic!("Expected a stumble"),
+
+        Err(e) => e,
+    }
+}
+
 #[allow(dead_code)]
 fn interpret_stdout(input: &str) {
     let mut walker = TreeWalker::default();
@@ -81,6 +120,15 @@ print (a * b) / (a + b);
 ";
         test_io(input, "1.5");
     }
+
+    #[test]
+    fn print_cycles() {
+        test_io("var l = [1]; l[0] = l; print l;", "[[...]]");
+
+        test_io("var m = {}; m[0] = [m]; print m;", "{0: [{...}]}");
+
+        test_io("var l = [1]; print [l, l];", "[[1], [1]]");
+    }
 }
 
 #[cfg(test)]
@@ -117,6 +165,35 @@ print a;
 ";
         test_io(input, "nil");
     }
+
+    #[test]
+    fn redeclaration() {
+        let input = "
+var a = 1;
+var a = a + 1;
+print a;
+";
+        test_io(input, "2");
+    }
+
+    #[test]
+    fn shadowing() {
+        let input = "
+var a = 1;
+{
+    var a = a + 2;
+    {
+        var b = a;
+        var a = 4;
+        print b;
+        print a;
+    }
+    print a;
+}
+print a;
+";
+        test_io(input, "3\n4\n3\n1");
+    }
 }
 
 #[cfg(test)]
@@ -410,3 +487,2104 @@ print add(1, 2, 3);
         test_io(input, "6");
     }
 }
+
+#[cfg(test)]
+mod maps {
+    use super::*;
+
+    #[test]
+    fn map_literal() {
+        let input = r#"
+var m = {"a": 1, "b": 2, 3: true, nil: "nil"};
+print m;
+print {};
+"#;
+        test_io(input, "{\"a\": 1, \"b\": 2, 3: true, nil: \"nil\"}\n{}");
+    }
+
+    #[test]
+    fn map_round_trip() {
+        let input = r#"
+var m = {"a": [1, "b", {false: nil}], "c": {}};
+print m;
+"#;
+        let expected = r#"{"a": [1, "b", {false: nil}], "c": {}}"#;
+        test_io(input, expected);
+
+        test_io(&format!("print {expected};"), expected);
+    }
+
+    #[test]
+    fn map_get_set() {
+        let input = r#"
+var m = {"a": 1};
+m["b"] = 2;
+m["a"] = m["a"] + m["b"];
+print m["a"];
+print m["missing"];
+print m;
+"#;
+        test_io(input, "3\nnil\n{\"a\": 3, \"b\": 2}");
+    }
+
+    #[test]
+    fn map_reference() {
+        let input = r#"
+var m = {};
+var n = m;
+n[1] = "one";
+print m[1];
+"#;
+        test_io(input, "one");
+    }
+
+    #[test]
+    fn map_block_statement() {
+        let input = r#"
+{"a": 1};
+{ print "block"; }
+"#;
+        test_io(input, "block");
+    }
+
+    #[test]
+    fn map_builtins() {
+        let input = r#"
+var m = {"z": 1, "y": 2, "x": 3};
+print keys(m);
+print values(m);
+print has(m, "y");
+print delete(m, "y");
+print has(m, "y");
+print delete(m, "y");
+m["y"] = 4;
+print keys(m);
+print len(m);
+"#;
+        test_io(
+            input,
+            "[\"z\", \"y\", \"x\"]\n[1, 2, 3]\ntrue\n2\nfalse\nnil\n[\"z\", \"x\", \"y\"]\n3",
+        );
+    }
+
+    #[test]
+    fn list_get_set() {
+        let input = r#"
+var l = [1, 2, 3];
+l[0] = l[1] + l[2];
+print l;
+print len(l);
+"#;
+        test_io(input, "[5, 2, 3]\n3");
+    }
+
+    #[test]
+    fn map_unhashable() {
+        assert_eq!(
+            test_stumble("var m = {}; m[[]] = 1;"),
+            StumbleKind::UnhashableKey
+        );
+
+        assert_eq!(test_stumble("var m = {[]: 1};"), StumbleKind::UnhashableKey);
+    }
+
+    #[test]
+    fn list_out_of_bounds() {
+        assert_eq!(
+            test_stumble("var l = [1]; print l[1];"),
+            StumbleKind::IndexOutOfBounds {
+                index: 1,
+                length: 1
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod iteration {
+    use super::*;
+
+    #[test]
+    fn for_in_list() {
+        let input = r#"
+var total = 0;
+for (x in [1, 2, 3]) {
+    total = total + x;
+    print x;
+}
+print total;
+"#;
+        test_io(input, "1\n2\n3\n6");
+    }
+
+    #[test]
+    fn for_in_map() {
+        let input = r#"
+var m = {"b": 1, "a": 2};
+for (k in m) {
+    print k;
+    print m[k];
+}
+"#;
+        test_io(input, "b\n1\na\n2");
+    }
+
+    #[test]
+    fn loop_body_block() {
+        assert_eq!(
+            test_stumble("for (x in [1]) print x; print 2;"),
+            StumbleKind::ExpectedBlock
+        );
+
+        assert_eq!(
+            test_stumble("while (false) print 1; print 2;"),
+            StumbleKind::ExpectedBlock
+        );
+    }
+
+    #[test]
+    fn for_in_string() {
+        let input = r#"
+for (c in "αβc") {
+    print c;
+}
+"#;
+        test_io(input, "α\nβ\nc");
+    }
+
+    #[test]
+    fn for_in_range() {
+        test_io("for (i in 0..3) { print i; }", "0\n1\n2");
+
+        test_io("for (i in 0..=3) { print i; }", "0\n1\n2\n3");
+
+        test_io("var n = 2; for (i in n..n + 2) { print i; }", "2\n3");
+
+        test_io("for (i in 3..0) { print i; }", "");
+    }
+
+    #[test]
+    fn for_in_break() {
+        let input = r#"
+for (i in 0..10) {
+    if (2 < i) {
+        break;
+    }
+    print i;
+}
+"#;
+        test_io(input, "0\n1\n2");
+    }
+
+    #[test]
+    fn for_in_closure() {
+        let input = r#"
+fun counter(limit) {
+    var i = 0;
+    fun next() {
+        i = i + 1;
+        var result = nil;
+        if (i <= limit) result = i;
+        return result;
+    }
+    return next;
+}
+
+for (i in counter(3)) {
+    print i;
+}
+
+var object = {"next": counter(2)};
+for (i in object) {
+    print i;
+}
+"#;
+        test_io(input, "1\n2\n3\n1\n2");
+    }
+
+    #[test]
+    fn for_in_fresh_binding() {
+        let input = r#"
+var fs = [];
+for (i in ["a", "b"]) {
+    fun f() {
+        return i;
+    }
+    fs = [f, fs];
+}
+print fs[0]();
+print fs[1][0]();
+"#;
+        test_io(input, "b\na");
+    }
+
+    #[test]
+    fn for_in_not_iterable() {
+        assert_eq!(
+            test_stumble("for (x in true) { print x; }"),
+            StumbleKind::NotIterable
+        );
+    }
+}
+
+#[cfg(test)]
+mod jumps {
+    use super::*;
+
+    #[test]
+    fn continue_while() {
+        let input = r#"
+var a = 0;
+while (a < 5) {
+    a = a + 1;
+    if (a == 2) {
+        continue;
+    }
+    print a;
+}
+"#;
+        test_io(input, "1\n3\n4\n5");
+    }
+
+    #[test]
+    fn continue_for_increment() {
+        let input = r#"
+for (var a = 0; a < 5; a = a + 1) {
+    if (a == 1 or a == 3) {
+        continue;
+    }
+    print a;
+}
+"#;
+        test_io(input, "0\n2\n4");
+    }
+
+    #[test]
+    fn continue_for_in() {
+        let input = r#"
+for (x in 0..4) {
+    if (x == 2) {
+        continue;
+    }
+    print x;
+}
+"#;
+        test_io(input, "0\n1\n3");
+    }
+
+    #[test]
+    fn break_skips_rest_of_block() {
+        let input = r#"
+loop {
+    {
+        break;
+        print "skipped";
+    }
+}
+print "done";
+"#;
+        test_io(input, "done");
+    }
+
+    #[test]
+    fn labelled_break() {
+        let input = r#"
+outer: for (var a = 0; a < 3; a = a + 1) {
+    for (b in 0..3) {
+        if (b == 2) {
+            continue outer;
+        }
+        if (a == 2) {
+            break outer;
+        }
+        print a + b;
+    }
+}
+print "done";
+"#;
+        test_io(input, "0\n1\n1\n2\ndone");
+    }
+
+    #[test]
+    fn labelled_loop() {
+        let input = r#"
+var a = 0;
+outer: loop {
+    inner: while (true) {
+        a = a + 1;
+        if (a < 3) {
+            continue inner;
+        }
+        break outer;
+    }
+}
+print a;
+"#;
+        test_io(input, "3");
+    }
+
+    #[test]
+    fn return_from_loop() {
+        let input = r#"
+fun find(xs, target) {
+    for (x in xs) {
+        if (x == target) {
+            return "found";
+        }
+    }
+    return "missing";
+}
+print find([1, 2, 3], 2);
+print find([1, 2, 3], 4);
+"#;
+        test_io(input, "found\nmissing");
+    }
+
+    #[test]
+    fn jump_outside_loop() {
+        assert_eq!(test_stumble("break;"), StumbleKind::BreakOutsideLoop);
+
+        assert_eq!(test_stumble("continue;"), StumbleKind::ContinueOutsideLoop);
+
+        assert_eq!(
+            test_stumble("loop { fun f() { break; } }"),
+            StumbleKind::BreakOutsideLoop
+        );
+
+        assert_eq!(
+            test_stumble("a: loop { break b; }"),
+            StumbleKind::UnknownLabel {
+                label: "b".to_owned()
+            }
+        );
+
+        assert_eq!(test_stumble("a: { print 1; }"), StumbleKind::ExpectedLoop);
+    }
+}
+
+#[cfg(test)]
+mod conditionals {
+    use super::*;
+
+    fn grade(conditional: &str, n: usize) -> String {
+        format!("var n = {n};\n{conditional}")
+    }
+
+    #[test]
+    fn if_parens_statement() {
+        let input = r#"
+if (n < 2) print "low";
+else if (n < 4) print "mid";
+else print "high";
+print "after";
+"#;
+        test_io(&grade(input, 1), "low\nafter");
+        test_io(&grade(input, 3), "mid\nafter");
+        test_io(&grade(input, 5), "high\nafter");
+    }
+
+    #[test]
+    fn if_parens_block() {
+        let input = r#"
+if (n < 2) {
+    print "low";
+} else if (n < 4) {
+    print "mid";
+} else {
+    print "high";
+}
+print "after";
+"#;
+        test_io(&grade(input, 1), "low\nafter");
+        test_io(&grade(input, 3), "mid\nafter");
+        test_io(&grade(input, 5), "high\nafter");
+    }
+
+    #[test]
+    fn if_bare_block() {
+        let input = r#"
+if n < 2 {
+    print "low";
+} else if n < 4 {
+    print "mid";
+} else {
+    print "high";
+}
+print "after";
+"#;
+        test_io(&grade(input, 1), "low\nafter");
+        test_io(&grade(input, 3), "mid\nafter");
+        test_io(&grade(input, 5), "high\nafter");
+    }
+
+    #[test]
+    fn if_mixed() {
+        let input = r#"
+if n < 2 {
+    print "low";
+} else if (n < 4) print "mid";
+else if (n < 6) {
+    print "high";
+} else print "higher";
+print "after";
+"#;
+        test_io(&grade(input, 1), "low\nafter");
+        test_io(&grade(input, 3), "mid\nafter");
+        test_io(&grade(input, 5), "high\nafter");
+        test_io(&grade(input, 7), "higher\nafter");
+    }
+
+    #[test]
+    fn if_parens_compound_condition() {
+        test_io("if (1) + 1 == 2 { print \"ok\"; }", "ok");
+    }
+
+    #[test]
+    fn if_empty_branch() {
+        test_io("if (true) ; else print \"nok\"; print \"ok\";", "ok");
+    }
+
+    #[test]
+    fn if_bare_statement() {
+        assert_eq!(
+            test_stumble("if true print \"nok\";"),
+            StumbleKind::ExpectedBlock
+        );
+    }
+
+    #[test]
+    fn if_declaration_branch() {
+        assert_eq!(
+            test_stumble("if (true) var a = 1;"),
+            StumbleKind::Unexpected(4)
+        );
+    }
+
+    #[test]
+    fn ternary() {
+        test_io("print true ? 1 : 2;", "1");
+
+        test_io("print nil ? 1 : 2;", "2");
+
+        test_io(
+            "var n = 3; print n < 2 ? \"low\" : n < 4 ? \"mid\" : \"high\";",
+            "mid",
+        );
+
+        test_io("var a; a = 1 < 2 ? \"yes\" : \"no\"; print a;", "yes");
+
+        test_io("print {true ? \"a\" : \"b\": 1};", "{\"a\": 1}");
+    }
+
+    #[test]
+    fn ternary_short_circuit() {
+        let input = r#"
+fun loud(x) {
+    print x;
+    return x;
+}
+print true ? loud(1) : loud(2);
+"#;
+        test_io(input, "1\n1");
+    }
+}
+
+#[cfg(test)]
+mod updates {
+    use super::*;
+
+    #[test]
+    fn compound_identifier() {
+        let input = r#"
+var a = 10;
+a += 5;
+print a;
+a -= 3;
+print a;
+a *= 2;
+print a;
+a /= 8;
+print a;
+a %= 2;
+print a;
+var s = "a";
+s += "b";
+print s;
+"#;
+        test_io(input, "15\n12\n24\n3.0\n1.0\nab");
+    }
+
+    #[test]
+    fn compound_value() {
+        test_io("var a = 1; var b = a += 2; print a; print b;", "3\n3");
+    }
+
+    #[test]
+    fn increments() {
+        let input = r#"
+var a = 1;
+print a++;
+print a;
+print ++a;
+print a--;
+print --a;
+print -a++;
+print a;
+"#;
+        test_io(input, "1\n2\n3\n3\n1\n-1\n2");
+    }
+
+    #[test]
+    fn for_increment() {
+        test_io("for (var i = 0; i < 3; i++) { print i; }", "0\n1\n2");
+    }
+
+    #[test]
+    fn compound_index() {
+        let input = r#"
+var l = [1, 2];
+l[0] += 10;
+l[1]++;
+print l;
+var m = {"a": 1};
+m["a"] *= 3;
+print --m["a"];
+print m;
+"#;
+        test_io(input, "[11, 3]\n2\n{\"a\": 2}");
+    }
+
+    #[test]
+    fn compound_index_once() {
+        let input = r#"
+var l = [0, 0];
+var calls = 0;
+fun index() {
+    calls++;
+    return 1;
+}
+l[index()] += 5;
+l[index()]++;
+print l;
+print calls;
+"#;
+        test_io(input, "[0, 6]\n2");
+    }
+
+    #[test]
+    fn update_invalid_target() {
+        assert_eq!(test_stumble("1 += 2;"), StumbleKind::InvalidAsignee);
+
+        assert_eq!(
+            test_stumble("var a; (a + 1)++;"),
+            StumbleKind::InvalidAsignee
+        );
+    }
+}
+
+#[cfg(test)]
+mod arithmetic {
+    use super::*;
+
+    #[test]
+    fn left_associative() {
+        test_io("print 8 - 2 - 1; print 8 / 2 / 2;", "5\n2.0");
+    }
+
+    #[test]
+    fn modulo() {
+        test_io(
+            "print 7 % 3; print -7 % 3; print 7 % -3; print -7 % -3;",
+            "1\n2\n-2\n-1",
+        );
+
+        test_io("print 7.5 % 2;", "1.5");
+    }
+
+    #[test]
+    fn integer_division() {
+        test_io("print 7 ~/ 2; print -7 ~/ 2; print 7 ~/ -2;", "3\n-4\n-4");
+
+        let input = r#"
+for (a in [7, -7]) {
+    for (b in [3, -3]) {
+        print a == (a ~/ b) * b + a % b;
+    }
+}
+"#;
+        test_io(input, "true\ntrue\ntrue\ntrue");
+    }
+
+    #[test]
+    fn exponent() {
+        test_io(
+            "print 2 ** 10; print 2 ** 3 ** 2; print -2 ** 2; print 2 ** -1; print (-2) ** 2;",
+            "1024\n512\n-4\n0.5\n4",
+        );
+    }
+
+    #[test]
+    fn non_numeric() {
+        assert_eq!(
+            test_stumble("print true % 2;"),
+            StumbleKind::TypeError {
+                op: "%".to_owned(),
+                left: Some("boolean"),
+                right: "integer"
+            }
+        );
+
+        assert_eq!(
+            test_stumble("print 2 ** nil;"),
+            StumbleKind::TypeError {
+                op: "**".to_owned(),
+                left: Some("integer"),
+                right: "nil"
+            }
+        );
+
+        assert_eq!(
+            test_stumble("print [] ~/ 2;"),
+            StumbleKind::TypeError {
+                op: "~/".to_owned(),
+                left: Some("list"),
+                right: "integer"
+            }
+        );
+
+        assert_eq!(
+            test_stumble("print \"2\" ** 2;"),
+            StumbleKind::TypeError {
+                op: "**".to_owned(),
+                left: Some("string"),
+                right: "integer"
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod bitwise {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        test_io(
+            "print 12 & 10; print 12 | 10; print 12 ^ 10; print ~0; print ~5;",
+            "8\n14\n6\n-1\n-6",
+        );
+
+        test_io(
+            "print 1 << 4; print 256 >> 4; print -8 >> 1; print 7 >> 63;",
+            "16\n16\n-4\n0",
+        );
+    }
+
+    #[test]
+    fn flags() {
+        let input = r#"
+var read = 1 << 0;
+var write = 1 << 1;
+var flags = read | write;
+print flags & write != 0;
+flags = flags & ~write;
+print flags;
+"#;
+        test_io(input, "true\n1");
+    }
+
+    #[test]
+    fn safe_range() {
+        test_io("print 1 << 52;", "4503599627370496");
+
+        test_io("print (2 ** 53 - 1) | 0;", "9007199254740991");
+
+        test_io("print 1 << 62;", "4611686018427387904");
+
+        assert_eq!(test_stumble("print 1 << 63;"), StumbleKind::BitwiseOverflow);
+
+        test_io("print 2.0 ** 52 | 0;", "4503599627370496");
+
+        assert_eq!(
+            test_stumble("print 2.0 ** 53 | 0;"),
+            StumbleKind::NotSafeIntegral
+        );
+
+        assert_eq!(test_stumble("print 1.5 & 1;"), StumbleKind::NotSafeIntegral);
+
+        assert_eq!(
+            test_stumble("print ~nil;"),
+            StumbleKind::TypeError {
+                op: "~".to_owned(),
+                left: None,
+                right: "nil"
+            }
+        );
+    }
+
+    #[test]
+    fn shift_amount() {
+        assert_eq!(test_stumble("print 1 << -1;"), StumbleKind::InvalidShift);
+
+        assert_eq!(test_stumble("print 1 >> 64;"), StumbleKind::InvalidShift);
+    }
+}
+
+#[cfg(test)]
+mod integers {
+    use super::*;
+
+    #[test]
+    fn literals() {
+        test_io(
+            "print 7; print 7.0; print 1e3; print 2.5e-3;",
+            "7\n7.0\n1000.0\n0.0025",
+        );
+
+        test_io("print 9007199254740992 + 1;", "9007199254740993");
+    }
+
+    #[test]
+    fn promotion() {
+        test_io(
+            "print 1 + 2; print 1 + 2.0; print 3 * 0.5; print 7 / 2; print 6 / 3;",
+            "3\n3.0\n1.5\n3.5\n2.0",
+        );
+
+        test_io(
+            "print 7 ~/ 2; print -7 ~/ 2; print 7.0 ~/ 2; print 2 ** 10; print 2 ** -1;",
+            "3\n-4\n3.0\n1024\n0.5",
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        test_io(
+            "print 9223372036854775807 + 1; print -9223372036854775807 - 2; print 2 ** 63;",
+            "9223372036854775808\n-9223372036854775809\n9223372036854775808",
+        );
+
+        test_io("print 2 ** 62 + (2 ** 62 - 1);", "9223372036854775807");
+    }
+
+    #[test]
+    fn division_by_zero() {
+        assert_eq!(test_stumble("print 1 % 0;"), StumbleKind::DivisionByZero);
+
+        assert_eq!(test_stumble("print 1 ~/ 0;"), StumbleKind::DivisionByZero);
+
+        test_io("print 1 / 0; print 1.0 ~/ 0;", "inf\ninf");
+    }
+
+    #[test]
+    fn equality() {
+        test_io(
+            "print 1 == 1.0; print 1 != 1.5; print 1 < 1.5; print 2 >= 2.0;",
+            "true\ntrue\ntrue\ntrue",
+        );
+
+        // 2^53 + 1 is not representable as a float, so the literal rounds to 2^53.
+        test_io(
+            "print 9007199254740993 == 9007199254740993.0; print 9007199254740992 == 9007199254740993.0;",
+            "false\ntrue",
+        );
+
+        test_io(
+            r#"var m = {1: "a"}; print m[1.0]; m[2.0] = "b"; print m;"#,
+            "a\n{1: \"a\", 2: \"b\"}",
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        test_io(
+            r#"print int(3.7); print int(-3.7); print int("42"); print float(3); print float("2.5");"#,
+            "3\n-3\n42\n3.0\n2.5",
+        );
+
+        assert_eq!(
+            test_stumble(r#"print int("4.5");"#),
+            StumbleKind::InvalidConversion
+        );
+
+        test_io("print int(2.0 ** 70) == 2 ** 70;", "true");
+
+        assert_eq!(
+            test_stumble("print int(nil);"),
+            StumbleKind::ConflictingSubexpression
+        );
+    }
+}
+
+#[cfg(test)]
+mod big_integers {
+    use super::*;
+
+    const FACTORIAL: &str = r#"
+fun factorial(n) {
+    var product = 1;
+    for (i in 2..=n) {
+        product *= i;
+    }
+    return product;
+}
+"#;
+
+    #[test]
+    fn factorials() {
+        let input =
+            format!("{FACTORIAL} print factorial(20); print factorial(25); print factorial(50);");
+        test_io(
+            &input,
+            "2432902008176640000\n15511210043330985984000000\n30414093201713378043612608166064768844377641568960512000000000000",
+        );
+
+        let input = format!("{FACTORIAL} print factorial(100);");
+        test_io(
+            &input,
+            "93326215443944152681699238856266700490715968264381621468592963895217599993229915608941463976156518286253697920827223758251185210916864000000000000000000000000",
+        );
+    }
+
+    #[test]
+    fn factorial_division() {
+        let input = format!(
+            "{FACTORIAL} print factorial(100) ~/ factorial(98); print factorial(30) % 1000000007; print -factorial(30) % 1000000007;"
+        );
+        test_io(&input, "9900\n109361473\n890638534");
+    }
+
+    #[test]
+    fn demotion() {
+        test_io(
+            "var a = 2 ** 64; print a - 2 ** 64 + 1; print (a ~/ 2 ** 32) << 1;",
+            "1\n8589934592",
+        );
+    }
+
+    #[test]
+    fn literals() {
+        test_io(
+            "print 0xff; print 0x7fffffffffffffff; print 0x10000000000000000; print 18446744073709551616 == 2 ** 64;",
+            "255\n9223372036854775807\n18446744073709551616\ntrue",
+        );
+    }
+
+    #[test]
+    fn comparison() {
+        test_io(
+            "print 2 ** 64 > 2 ** 63; print -(2 ** 64) < 1; print 2 ** 64 == 2.0 ** 64; print 2 ** 64 + 1 == 2.0 ** 64; print 2 ** 64 < 1e300;",
+            "true\ntrue\ntrue\nfalse\ntrue",
+        );
+
+        test_io(
+            "var m = {}; m[2 ** 64] = 1; print m[2.0 ** 64]; print 2 ** 64 / 2 ** 63;",
+            "1\n2.0",
+        );
+    }
+
+    #[test]
+    fn division_beyond_floats() {
+        // Each operand is beyond the range of a float, though each quotient is not.
+        test_io(
+            "print 10 ** 400 / 10 ** 399; print 10 ** 400 / (3 * 10 ** 399); print -(10 ** 400) / (3 * 10 ** 399); print 10 ** 399 / 10 ** 400;",
+            "10.0\n3.3333333333333335\n-3.3333333333333335\n0.1",
+        );
+
+        test_io("print 1 / 10 ** 400; print 10 ** 400 / 3;", "0.0\ninf");
+    }
+
+    #[test]
+    fn exponents() {
+        test_io(
+            "print 2 ** 100; print (-1) ** 10000000000; print 2 ** -2;",
+            "1267650600228229401496703205376\n1\n0.25",
+        );
+
+        assert_eq!(
+            test_stumble("print 2 ** 10000000000;"),
+            StumbleKind::IntegerOverflow
+        );
+    }
+
+    #[test]
+    fn large_powers() {
+        test_io(
+            "print 0 ** 3000000; print 1 ** 3000000; print (-1) ** 3000001; print 2 ** 32000 > 0;",
+            "0\n1\n-1\ntrue",
+        );
+
+        // The size of a power is estimated before the power is computed.
+        assert_eq!(
+            test_stumble("print 2 ** 3000000;"),
+            StumbleKind::IntegerOverflow
+        );
+
+        assert_eq!(
+            test_stumble("print math.pow(3, 3000000);"),
+            StumbleKind::IntegerOverflow
+        );
+    }
+}
+
+#[cfg(test)]
+mod interpolation {
+    use super::*;
+
+    #[test]
+    fn interpolate() {
+        let input = r#"
+var name = "Ada";
+var age = 36;
+print "Hello ${name}, you are ${age + 1}";
+"#;
+        test_io(input, "Hello Ada, you are 37");
+
+        test_io(r#"print "${1}${2}"; print "${""}";"#, "12");
+    }
+
+    #[test]
+    fn display() {
+        test_io(
+            r#"print "${[1, "a"]} ${nil} ${1.0} ${true}";"#,
+            r#"[1, "a"] nil 1.0 true"#,
+        );
+    }
+
+    #[test]
+    fn nested() {
+        test_io(
+            r#"print "${ {"a": 1}["a"] } and ${"inner ${1 + 1}"}";"#,
+            "1 and inner 2",
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        test_io(
+            r#"print "\${x} \"q\" a\\b $5 \d";"#,
+            r#"${x} "q" a\b $5 \d"#,
+        );
+
+        test_io(r#"print ["\${", "a\"b"];"#, r#"["\${", "a\"b"]"#);
+    }
+
+    #[test]
+    fn error_location() {
+        let located = stumble("var x = 1;\nprint \"value: ${x + nil}\";");
+
+        assert_eq!(
+            located.kind,
+            StumbleKind::TypeError {
+                op: "+".to_owned(),
+                left: Some("integer"),
+                right: "nil"
+            }
+        );
+        assert_eq!(located.location, Location::new(1, 16));
+
+        let located = stumble("print \"${\"${1 % 0}\"}\";");
+
+        assert_eq!(located.kind, StumbleKind::DivisionByZero);
+        assert_eq!(located.location, Location::new(0, 12));
+    }
+}
+
+#[cfg(test)]
+mod strings {
+    use super::*;
+
+    #[test]
+    fn methods() {
+        let input = r#"
+var s = "  Hello, World  ";
+print s.trim();
+print s.trim().upper();
+print s.lower().trim();
+print s.len();
+print s.contains("World");
+print s.contains("world");
+print s.replace("l", "L");
+"#;
+        test_io(
+            input,
+            "Hello, World\nHELLO, WORLD\nhello, world\n16\ntrue\nfalse\n  HeLLo, WorLd",
+        );
+    }
+
+    #[test]
+    fn split_and_chars() {
+        test_io(
+            r#"print "a,b,,c".split(","); print "abc".split(""); print "ab".chars();"#,
+            r#"["a", "b", "", "c"]
+["a", "b", "c"]
+["a", "b"]"#,
+        );
+    }
+
+    #[test]
+    fn unicode() {
+        let input = r#"
+var s = "héllo wörld";
+print s.len();
+print len(s);
+print s.substr(1, 5);
+print s.substr(7, 11);
+print s.chars()[1];
+print "ß".upper();
+"#;
+        test_io(input, "11\n11\néllo\nörld\né\nSS");
+    }
+
+    #[test]
+    fn substr_bounds() {
+        test_io(
+            r#"print "abc".substr(0, 0) == ""; print "abc".substr(0, 3);"#,
+            "true\nabc",
+        );
+
+        assert_eq!(
+            test_stumble(r#"print "abc".substr(0, 4);"#),
+            StumbleKind::IndexOutOfBounds {
+                index: 4,
+                length: 3
+            }
+        );
+
+        assert_eq!(
+            test_stumble(r#"print "abc".substr(2, 1);"#),
+            StumbleKind::IndexOutOfBounds {
+                index: 2,
+                length: 3
+            }
+        );
+
+        assert_eq!(
+            test_stumble(r#"print "abc".substr(-1, 1);"#),
+            StumbleKind::InvalidIndex
+        );
+    }
+
+    #[test]
+    fn bound_methods() {
+        test_io(
+            r#"var upper = "abc".upper; print upper(); print upper; for (c in "ab".chars()) { print c.upper(); }"#,
+            "ABC\n<native upper>\nA\nB",
+        );
+
+        assert_eq!(
+            test_stumble(r#"print "abc".upper(1);"#),
+            StumbleKind::ArityMismatch {
+                expected: 0,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_property() {
+        assert_eq!(
+            test_stumble(r#"print "abc".reverse();"#),
+            StumbleKind::UnknownProperty {
+                name: "reverse".to_owned()
+            }
+        );
+
+        assert_eq!(
+            test_stumble("var x = 1; print x.len();"),
+            StumbleKind::UnknownProperty {
+                name: "len".to_owned()
+            }
+        );
+
+        assert_eq!(
+            test_stumble(r#"print "a".1;"#),
+            StumbleKind::ExpectedProperty
+        );
+    }
+}
+
+mod math {
+    use super::*;
+
+    #[test]
+    fn functions() {
+        let input = r#"
+print math.sqrt(16);
+print math.pow(2, 10);
+print math.pow(2, 0.5) == math.sqrt(2);
+print math.floor(2.5);
+print math.ceil(-2.5);
+print math.round(2.5);
+print math.floor(7);
+print math.abs(-3);
+print math.abs(-3.5);
+print math.min(1, 2.5);
+print math.max(1, 2.5);
+print math.log(math.E);
+print math.log10(1000);
+print math.exp(0);
+print math.atan2(1, 1) * 4 == math.PI;
+"#;
+        test_io(
+            input,
+            "4.0\n1024\ntrue\n2.0\n-2.0\n3.0\n7\n3\n3.5\n1\n2.5\n1.0\n3.0\n1.0\ntrue",
+        );
+    }
+
+    #[test]
+    fn trigonometry() {
+        test_io(
+            "print math.sin(0); print math.cos(0); print math.tan(0); print math.asin(1) * 2 == math.PI;",
+            "0.0\n1.0\n0.0\ntrue",
+        );
+    }
+
+    #[test]
+    fn nan() {
+        let input = r#"
+print math.NAN == math.NAN;
+print math.is_nan(math.NAN);
+print math.is_nan(1);
+print math.is_nan(math.INF - math.INF);
+print math.INF > 10 ** 100;
+"#;
+        test_io(input, "false\ntrue\nfalse\ntrue\ntrue");
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(
+            test_stumble("print math.tau;"),
+            StumbleKind::UnknownProperty {
+                name: "tau".to_owned()
+            }
+        );
+
+        assert_eq!(
+            test_stumble(r#"print math.sqrt("four");"#),
+            StumbleKind::ConflictingSubexpression
+        );
+    }
+}
+
+mod equality {
+    use super::*;
+
+    #[test]
+    fn nil() {
+        test_io(
+            "var x; print nil == nil; print x == nil; print x != nil; print 0 == nil; print false == nil;",
+            "true\ntrue\nfalse\nfalse\nfalse",
+        );
+    }
+
+    #[test]
+    fn functions() {
+        let input = r#"
+fun counter() {
+    var count = 0;
+    fun increment() {
+        count = count + 1;
+        return count;
+    }
+    return increment;
+}
+
+var a = counter();
+var b = counter();
+var c = a;
+print a == a;
+print a == c;
+print a == b;
+print counter == counter;
+print len == len;
+print "ab".len == "ab".len;
+print "ab".len == "cd".len;
+"#;
+        test_io(input, "true\ntrue\nfalse\ntrue\ntrue\ntrue\nfalse");
+    }
+
+    #[test]
+    fn declarations() {
+        // Each evaluation of a declaration is a distinct function, though within the same environment.
+        let input = r#"
+var fs = [nil, nil];
+var i = 0;
+while (i < 2) {
+    fun f() { return i; }
+    fs[i] = f;
+    i += 1;
+}
+print fs[0] == fs[1];
+print fs[0] == fs[0];
+print fs[0]() == fs[1]();
+"#;
+        test_io(input, "false\ntrue\ntrue");
+    }
+
+    #[test]
+    fn same() {
+        let input = r#"
+var l = [1];
+print same(l, l);
+print same(l, [1]);
+print same(1, 1);
+print same(1, 1.0);
+print 1 == 1.0;
+print same(math.NAN, math.NAN);
+print same("a", "a");
+print same(nil, nil);
+"#;
+        test_io(input, "true\nfalse\ntrue\nfalse\ntrue\ntrue\ntrue\ntrue");
+    }
+}
+
+mod exceptions {
+    use super::*;
+
+    use crate::interpreter::ast::expression::ExprB;
+
+    #[test]
+    fn throw_and_catch() {
+        let input = r#"
+try {
+    print "before";
+    throw "oops";
+    print "after";
+} catch (e) {
+    print "caught " + e;
+}
+print "done";
+"#;
+        test_io(input, "before\ncaught oops\ndone");
+    }
+
+    #[test]
+    fn runtime_errors() {
+        let input = r#"
+try {
+    var x = 1;
+    print x + nil;
+} catch (e) {
+    print e.message;
+    print e.line;
+}
+
+fun f(a) { return a; }
+try { f(); } catch (e) { print e.message; }
+try { print undefined; } catch (e) { print e.message; }
+try { print 1 % 0; } catch (e) { print e["message"]; }
+"#;
+        test_io(
+            input,
+            "unsupported operands for +: integer and nil\n4\nexpected 1 arguments, found 0\nundefined variable 'undefined'\ndivision by zero",
+        );
+    }
+
+    #[test]
+    fn throw_through_calls() {
+        let input = r#"
+fun inner(n) {
+    if (n == 0) { throw {"code": 42}; }
+    return inner(n - 1);
+}
+
+try { inner(5); } catch (e) { print e["code"]; }
+"#;
+        test_io(input, "42");
+    }
+
+    #[test]
+    fn finally() {
+        let input = r#"
+try { print "try"; } finally { print "finally"; }
+
+try {
+    throw 1;
+} catch (e) {
+    print "catch";
+} finally {
+    print "finally";
+}
+
+fun f() {
+    try {
+        return "returned";
+    } finally {
+        print "cleanup";
+    }
+}
+print f();
+
+for (i in 0..3) {
+    try {
+        if (i == 1) { continue; }
+        if (i == 2) { break; }
+        print i;
+    } finally {
+        print "after ${i}";
+    }
+}
+"#;
+        test_io(
+            input,
+            "try\nfinally\ncatch\nfinally\ncleanup\nreturned\n0\nafter 0\nafter 1\nafter 2",
+        );
+    }
+
+    #[test]
+    fn rethrow() {
+        let input = r#"
+try {
+    try {
+        throw "inner";
+    } catch (e) {
+        throw e + " again";
+    } finally {
+        print "inner finally";
+    }
+} catch (e) {
+    print e;
+}
+"#;
+        test_io(input, "inner finally\ninner again");
+    }
+
+    #[test]
+    fn uncaught() {
+        let uncaught = stumble("print 1;\nthrow \"oops\";");
+
+        assert_eq!(
+            uncaught.kind,
+            StumbleKind::Thrown {
+                value: ExprB::mk_string("oops".to_owned())
+            }
+        );
+        assert_eq!(uncaught.location, Location::new(1, 0));
+        assert_eq!(uncaught.kind.to_string(), "uncaught \"oops\"");
+
+        assert_eq!(
+            test_stumble("try { throw 1; } finally { print 2; }"),
+            StumbleKind::Thrown {
+                value: ExprB::mk_integer(1)
+            }
+        );
+    }
+
+    #[test]
+    fn expected_catch() {
+        assert_eq!(test_stumble("try { print 1; }"), StumbleKind::ExpectedCatch);
+    }
+}
+
+mod traceback {
+    use super::*;
+
+    use crate::interpreter::err::Frame;
+
+    #[test]
+    fn nested_calls() {
+        let input = "fun fib(n) {
+    if (n < 2) {
+        return n + nil;
+    }
+    return fib(n - 1) + fib(n - 2);
+}
+
+fun main() {
+    print fib(3);
+}
+
+main();";
+
+        let traced = stumble(input);
+
+        assert_eq!(
+            traced.trace,
+            vec![
+                Frame {
+                    name: "main".into(),
+                    location: Location::new(11, 4),
+                },
+                Frame {
+                    name: "fib".into(),
+                    location: Location::new(8, 13),
+                },
+                Frame {
+                    name: "fib".into(),
+                    location: Location::new(4, 14),
+                },
+                Frame {
+                    name: "fib".into(),
+                    location: Location::new(4, 14),
+                },
+            ]
+        );
+
+        assert_eq!(
+            traced.traceback(),
+            vec![
+                "at fib (line 3)",
+                "at fib (line 5)",
+                "at fib (line 5)",
+                "at main (line 9)",
+                "at script (line 12)",
+            ]
+        );
+    }
+
+    #[test]
+    fn top_level() {
+        let traced = stumble("var x = 1;\nprint x + nil;");
+
+        assert!(traced.trace.is_empty());
+        assert_eq!(traced.traceback(), vec!["at script (line 2)"]);
+    }
+
+    #[test]
+    fn thrown() {
+        let input = "fun fail() {\n    throw \"failed\";\n}\n\nfail();";
+
+        let traced = stumble(input);
+
+        assert_eq!(
+            traced.traceback(),
+            vec!["at fail (line 2)", "at script (line 5)"]
+        );
+    }
+
+    #[test]
+    fn iteration() {
+        let input = "fun next() {\n    return 1 + \"a\";\n}\n\nfor (x in next) {}";
+
+        assert_eq!(
+            stumble(input).traceback(),
+            vec!["at next (line 2)", "at script (line 5)"]
+        );
+    }
+
+    #[test]
+    fn caught() {
+        let input = r#"
+fun fail() { throw "failed"; }
+fun recover() {
+    try { fail(); } catch (e) { return e; }
+}
+print recover();
+print 1 + nil;
+"#;
+
+        assert_eq!(stumble(input).traceback(), vec!["at script (line 7)"]);
+    }
+
+    #[test]
+    fn repeated_frames() {
+        let input = "fun down(n) {\n    if (n == 0) { return nil + 1; }\n    return down(n - 1);\n}\n\ndown(40);";
+
+        assert_eq!(
+            stumble(input).traceback(),
+            vec![
+                "at down (line 2)",
+                "at down (line 3)",
+                "at down (line 3)",
+                "at down (line 3)",
+                "... repeated 37 more times",
+                "at script (line 6)",
+            ]
+        );
+
+        let input = "fun down(n) {\n    if (n == 0) { return nil + 1; }\n    return down(n - 1);\n}\n\ndown(4);";
+
+        assert_eq!(
+            stumble(input).traceback(),
+            vec![
+                "at down (line 2)",
+                "at down (line 3)",
+                "at down (line 3)",
+                "at down (line 3)",
+                "... repeated 1 more time",
+                "at script (line 6)",
+            ]
+        );
+    }
+}
+
+mod call_depth {
+    use super::*;
+
+    fn shallow() -> Config {
+        Config {
+            max_call_depth: 16,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn overflow() {
+        let input = "fun f(n) {\n    return f(n + 1);\n}\n\nf(0);";
+
+        let overflow = stumble_with(shallow(), input);
+
+        assert_eq!(overflow.kind, StumbleKind::StackOverflow { depth: 16 });
+        assert_eq!(overflow.trace.len(), 16);
+        assert_eq!(overflow.location.line, 1);
+    }
+
+    /// The depth of the stack overflow, if any, from interpreting `input` with the default configuration on a thread with the stack of a main thread.
+    fn main_thread_overflow(input: &'static str) -> Option<usize> {
+        // The stack of a main thread is greater than that of a test thread.
+        thread_overflow(8 << 20, input)
+    }
+
+    /// The depth of the stack overflow, if any, from interpreting `input` with the default configuration on a thread with a stack of `stack_size` bytes.
+    fn thread_overflow(stack_size: usize, input: &'static str) -> Option<usize> {
+        std::thread::Builder::new()
+            .stack_size(stack_size)
+            .spawn(|| match test_stumble(input) {
+                StumbleKind::StackOverflow { depth } => Some(depth),
+
+                _ => None,
+            })
+            .expect("Failed to spawn thread")
+            .join()
+            .expect("Overflowed the stack of the host")
+    }
+
+    #[test]
+    fn default_depth() {
+        // Without optimisation, the stack of a main thread may be exhausted before the depth is reached.
+        let depth = main_thread_overflow("fun f(n) { return f(n + 1); } f(0);");
+        assert!(depth.is_some_and(|depth| depth <= 512));
+    }
+
+    #[test]
+    fn default_stack() {
+        let input = "fun f(n) { if (n == 0) { return 0; } return 1 + f(n - 1); } print f(10000);";
+        assert!(main_thread_overflow(input).is_some());
+
+        let input = r#"
+fun f(n) {
+    if (n == 0) { return 0; }
+    var total = 0;
+    for (x in [1, 2]) {
+        while (total < 10) {
+            try { total = total + x * (1 + (2 * f(n - 1))); } finally { total = total + 1; }
+        }
+    }
+    return total;
+}
+print f(10000);
+"#;
+
+        assert!(main_thread_overflow(input).is_some());
+    }
+
+    #[test]
+    fn spawned_thread_stack() {
+        let input = r#"
+fun f(n) {
+    if (n == 0) { return 0; }
+    var total = 0;
+    for (x in [1, 2]) {
+        try { total = total + x * f(n - 1); } finally { total = total + 1; }
+    }
+    return total;
+}
+print f(10000);
+"#;
+
+        // The stack given to a spawned thread by default.
+        assert!(thread_overflow(2 << 20, input).is_some());
+    }
+
+    #[test]
+    fn stack() {
+        let config = Config {
+            max_stack: 64 << 10,
+            ..Config::default()
+        };
+
+        let overflow = stumble_with(
+            config,
+            "fun f(n) { if (n == 0) { return 0; } return 1 + f(n - 1); } print f(500);",
+        );
+
+        assert!(matches!(overflow.kind, StumbleKind::StackOverflow { depth } if depth < 500));
+    }
+
+    #[test]
+    fn within_depth() {
+        test_io_with(
+            shallow(),
+            "fun f(n) { if (n == 0) { return 0; } return 1 + f(n - 1); } print f(15);",
+            "15",
+        );
+    }
+
+    #[test]
+    fn catchable() {
+        let input = r#"
+fun f(n) { return f(n + 1); }
+
+var message;
+try { f(0); } catch (e) { message = e.message; }
+print message;
+print len(message);
+"#;
+
+        test_io_with(shallow(), input, "stack overflow, beyond 16 calls\n31");
+    }
+
+    #[test]
+    fn recovers() {
+        let input = r#"
+fun f(n) { return f(n + 1); }
+fun g(n) { if (n == 0) { return "recovered"; } return g(n - 1); }
+
+try { f(0); } catch (e) {}
+print g(15);
+"#;
+
+        test_io_with(shallow(), input, "recovered");
+    }
+}
+
+mod budgets {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::{Duration, Instant},
+    };
+
+    use super::*;
+
+    #[test]
+    fn step_limit() {
+        let config = Config {
+            max_steps: Some(1000),
+            ..Config::default()
+        };
+
+        assert_eq!(
+            stumble_with(config.clone(), "loop {}").kind,
+            StumbleKind::StepLimit { steps: 1000 }
+        );
+
+        assert_eq!(
+            stumble_with(config.clone(), "var i = 0; while (true) { i = i + 1; }").kind,
+            StumbleKind::StepLimit { steps: 1000 }
+        );
+
+        test_io_with(
+            config,
+            "var i = 0; while (i < 10) { i = i + 1; } print i;",
+            "10",
+        );
+    }
+
+    #[test]
+    fn uncatchable() {
+        let config = Config {
+            max_steps: Some(1000),
+            ..Config::default()
+        };
+
+        let input = r#"
+try {
+    loop {}
+} catch (e) {
+    print "caught";
+}
+print "continued";
+"#;
+
+        assert_eq!(
+            stumble_with(config, input).kind,
+            StumbleKind::StepLimit { steps: 1000 }
+        );
+    }
+
+    #[test]
+    fn deadline() {
+        let config = Config {
+            deadline: Some(Instant::now() + Duration::from_millis(20)),
+            ..Config::default()
+        };
+
+        assert_eq!(
+            stumble_with(config, "loop {}").kind,
+            StumbleKind::DeadlineExceeded
+        );
+    }
+
+    #[test]
+    fn deadline_through_finally() {
+        let config = Config {
+            deadline: Some(Instant::now() + Duration::from_millis(20)),
+            ..Config::default()
+        };
+
+        let input =
+            "fun f() { try { while (true) {} } finally { return 1; } } while (true) { f(); }";
+
+        assert_eq!(
+            stumble_with(config, input).kind,
+            StumbleKind::DeadlineExceeded
+        );
+    }
+
+    #[test]
+    fn cancelled() {
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let config = Config {
+            cancel: Some(cancel.clone()),
+            ..Config::default()
+        };
+
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            cancel.store(true, Ordering::Relaxed);
+        });
+
+        assert_eq!(
+            stumble_with(config, "fun spin() { loop {} } spin();").kind,
+            StumbleKind::Cancelled
+        );
+
+        canceller.join().expect("Failed to cancel");
+    }
+
+    #[test]
+    fn cancelled_through_finally() {
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let config = Config {
+            cancel: Some(cancel.clone()),
+            ..Config::default()
+        };
+
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            cancel.store(true, Ordering::Relaxed);
+        });
+
+        let input = "while (true) { try { loop {} } finally { continue; } }";
+
+        assert_eq!(stumble_with(config, input).kind, StumbleKind::Cancelled);
+
+        canceller.join().expect("Failed to cancel");
+    }
+}
+
+mod memory {
+    use super::*;
+
+    const LIMIT: usize = 1 << 20;
+
+    fn quota() -> Config {
+        Config {
+            max_memory: Some(LIMIT),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn strings() {
+        assert_eq!(
+            stumble_with(quota(), r#"var s = "x"; loop { s = s + s; }"#).kind,
+            StumbleKind::OutOfMemory { limit: LIMIT }
+        );
+
+        assert_eq!(
+            stumble_with(quota(), r#"var s = "x"; loop { s += "${s}"; }"#).kind,
+            StumbleKind::OutOfMemory { limit: LIMIT }
+        );
+    }
+
+    #[test]
+    fn collections() {
+        assert_eq!(
+            stumble_with(quota(), "var l = []; loop { l = [l, l, l, l]; }").kind,
+            StumbleKind::OutOfMemory { limit: LIMIT }
+        );
+
+        assert_eq!(
+            stumble_with(quota(), "var m = {}; var i = 0; loop { m[i] = i; i += 1; }").kind,
+            StumbleKind::OutOfMemory { limit: LIMIT }
+        );
+    }
+
+    #[test]
+    fn environments() {
+        // Each closure holds the environment of the call which made the closure, and so each such environment is live.
+        let input =
+            "fun wrap(f) { fun g() { return f; } return g; } var f = nil; loop { f = wrap(f); }";
+
+        assert_eq!(
+            stumble_with(quota(), input).kind,
+            StumbleKind::OutOfMemory { limit: LIMIT }
+        );
+    }
+
+    #[test]
+    fn freed() {
+        test_io_with(
+            quota(),
+            "var i = 0; while (i < 100000) { { var j = i; } i += 1; } print i;",
+            "100000",
+        );
+
+        test_io_with(
+            quota(),
+            "fun id(x) { return x; } var s = 0; for (var i = 0; i < 20000; i += 1) { s = id(s) + 1; } print s;",
+            "20000",
+        );
+
+        let input = r#"
+var pad = "x";
+for (var i = 0; i < 13; i += 1) { pad = pad + pad; }
+var s;
+for (var i = 0; i < 1000; i += 1) { s = pad + "{i}"; }
+print s.len();
+"#;
+
+        test_io_with(quota(), input, "8195");
+    }
+
+    #[test]
+    fn collected() {
+        let input = r#"
+fun cycle() { var l = [0, "x"]; l[0] = l; }
+for (var i = 0; i < 20000; i += 1) { cycle(); }
+print "collected";
+"#;
+
+        test_io_with(quota(), input, "collected");
+    }
+
+    #[test]
+    fn natives() {
+        // The result of each method exceeds the quota, and is not built.
+        let input = r#"
+var s = "x";
+for (var i = 0; i < 10; i += 1) { s = s + s; }
+print s.replace("x", s).len();
+"#;
+
+        assert_eq!(
+            stumble_with(quota(), input).kind,
+            StumbleKind::OutOfMemory { limit: LIMIT }
+        );
+
+        let input = r#"
+var s = "x";
+for (var i = 0; i < 15; i += 1) { s = s + s; }
+print s.chars().len();
+"#;
+
+        assert_eq!(
+            stumble_with(quota(), input).kind,
+            StumbleKind::OutOfMemory { limit: LIMIT }
+        );
+    }
+
+    #[test]
+    fn uncatchable() {
+        let input = r#"
+var s = "x";
+try {
+    loop { s = s + s; }
+} catch (e) {
+    print "caught";
+}
+"#;
+
+        assert_eq!(
+            stumble_with(quota(), input).kind,
+            StumbleKind::OutOfMemory { limit: LIMIT }
+        );
+    }
+
+    #[test]
+    fn within_quota() {
+        test_io_with(
+            quota(),
+            r#"var s = ""; for (var i = 0; i < 10; i += 1) { s = s + "x"; } print s.len();"#,
+            "10",
+        );
+    }
+}
+
+mod gc {
+    use super::*;
+
+    #[test]
+    fn closures() {
+        let input = r#"
+fun make() {
+    fun f() { return f; }
+    return f;
+}
+
+for (var i = 0; i < 10; i += 1) { make(); }
+
+print gc();
+print gc();
+"#;
+
+        // Each call leaves the environments of the arguments and the body of the call, and the closure of `f`.
+        test_io(input, "30\n0");
+    }
+
+    #[test]
+    fn lists() {
+        test_io("var l = [0]; l[0] = l; l = nil; print gc();", "1");
+        test_io("var m = {}; m[0] = m; m = nil; print gc();", "1");
+    }
+
+    #[test]
+    fn deeply_nested() {
+        let input = r#"
+var l = [];
+var m = {};
+for (var i = 0; i < 100000; i += 1) { l = [l]; m = {0: m}; }
+l = nil;
+m = nil;
+print "dropped";
+"#;
+        test_io(input, "dropped");
+    }
+
+    #[test]
+    fn live_closures() {
+        let input = r#"
+fun counter() {
+    var n = 0;
+    fun increment() { n += 1; return n; }
+    return increment;
+}
+
+var count = counter();
+count();
+print gc();
+print count();
+"#;
+
+        test_io(input, "0\n2");
+    }
+
+    #[test]
+    fn automatic() {
+        let mut walker = TreeWalker::default();
+
+        walker.scan("fun make() { fun f() { return f; } return f; } for (var i = 0; i < 10000; i += 1) { make(); }");
+        walker.parse().expect("Parser error");
+
+        let mut buffer = Vec::default();
+        let mut system = Base::default();
+        system.set_out(&mut buffer);
+
+        walker
+            .interpret_all(&mut system)
+            .expect("Interpreter error");
+
+        let stats = system.heap.stats();
+        assert!(0 < stats.collections);
+        assert!(stats.tracked < 4096);
+        assert!(10000 < stats.collected);
+    }
+}
+
+#[cfg(test)]
+mod optimise {
+    use super::*;
+
+    fn optimised() -> Config {
+        Config {
+            optimise: true,
+            ..Default::default()
+        }
+    }
+
+    /// Interpret `input` with and without optimisation, expecting `output` from each.
+    fn test_optimised(input: &str, output: &str) {
+        test_io(input, output);
+        test_io_with(optimised(), input, output);
+    }
+
+    /// Interpret `input` with and without optimisation, expecting the same stumble at the same location from each.
+    fn test_optimised_stumble(input: &str) {
+        let unoptimised = stumble(input);
+        let optimised = stumble_with(optimised(), input);
+
+        assert_eq!(unoptimised.kind, optimised.kind);
+        assert_eq!(unoptimised.location, optimised.location);
+    }
+
+    #[test]
+    fn folding() {
+        test_optimised("print 2 * 60 * 60;", "7200");
+        test_optimised(r#"print "a" + "b";"#, "ab");
+        test_optimised("print -(1 + 2) * 3;", "-9");
+        test_optimised("print !(1 < 2);", "false");
+        test_optimised(
+            "print 1 / 2 + 1; print 9223372036854775807 + 1;",
+            "1.5\n9223372036854775808",
+        );
+        test_optimised("print ~0 | 1 << 3;", "-1");
+        test_optimised(
+            r#"var x = 2; print x * (3 + 4); print "${1 + 1}";"#,
+            "14\n2",
+        );
+    }
+
+    #[test]
+    fn dead_statements() {
+        test_optimised(
+            "if (false) { print 1; } else { print 2; } if (1 == 1) print 3; ; ;",
+            "2\n3",
+        );
+
+        test_optimised("fun f() { return 1; print 2; } print f();", "1");
+
+        test_optimised(
+            "for (var i = 0; i < 3; i += 1) { if (i == 1) { continue; print i; } print i; }",
+            "0\n2",
+        );
+
+        test_optimised("while (true) { print 1; break; print 2; }", "1");
+    }
+
+    #[test]
+    fn large_powers() {
+        // A large power is left to be computed, if ever, when interpreted.
+        test_optimised(
+            "fun never() { return 7 ** 40000; } print 2 ** 100 == 2.0 ** 100;",
+            "true",
+        );
+    }
+
+    #[test]
+    fn stumbles() {
+        test_optimised_stumble("print 1 % 0;");
+        test_optimised_stumble("var x = 1;\nprint 1 + 2 * (3 ~/ 0);");
+        test_optimised_stumble("print \"${\"${1 % 0}\"}\";");
+        test_optimised_stumble("print -\"a\";");
+        test_optimised_stumble("print !1;");
+        test_optimised_stumble("print 1 << 64;");
+        test_optimised_stumble("if (true) { throw 1 + 1; }");
+
+        assert_eq!(
+            stumble_with(optimised(), "print 1 + nil;").kind,
+            StumbleKind::TypeError {
+                op: "+".to_owned(),
+                left: Some("integer"),
+                right: "nil"
+            }
+        );
+    }
+}
@@ -2,12 +2,29 @@
 
 use std::io::{BufRead, Read, Write};
 
-use loxy_lib::interpreter::{Base, TreeWalker};
+use loxy_lib::interpreter::{Base, TreeWalker, config::Config};
+
+/// The size of the stack of the thread the interpreter runs on, ample for the maximum call depth of the interpreter.
+///
+/// Half of the stack may be used by calls, with the remainder left for the call in progress.
+const STACK_SIZE: usize = 64 << 20;
 
 fn main() {
+    let repl = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(repl)
+        .expect("Failed to spawn the interpreter thread");
+
+    let _ = repl.join();
+}
+
+fn repl() {
     let stdin = std::io::stdin();
 
-    let mut walker = TreeWalker::default();
+    let mut walker = TreeWalker::with_config(Config {
+        max_stack: STACK_SIZE / 2,
+        ..Config::default()
+    });
     let mut base = Base::default();
 
     let mut statement_count = 0;
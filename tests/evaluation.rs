@@ -3,13 +3,14 @@ mod evaluation {
     use loxy_lib::interpreter::{
         Base, TreeWalker,
         ast::expression::{Expr, ExprB, OpOne, OpTwo},
-        environment::Env,
+        config::Config,
+        err::StumbleKind,
     };
 
     #[test]
     fn basic_negation() {
-        let interpreter = TreeWalker::default();
-        let env = Env::fresh_std_env();
+        let mut interpreter = TreeWalker::default();
+        let env = interpreter.fresh_std_env();
 
         let number = Expr::from(64.0);
 
@@ -19,21 +20,76 @@ mod evaluation {
             interpreter.eval(&number_negation, &env, &mut Base::default()),
             Ok(ExprB::mk_numeric(-64.0))
         );
+    }
 
-        let string = Expr::from("64");
+    #[test]
+    fn string_coercion() {
+        let mut interpreter = TreeWalker::with_config(Config {
+            string_coercion: true,
+            ..Config::default()
+        });
+        let env = interpreter.fresh_std_env();
 
-        let string_negation = Expr::mk_unary(OpOne::Minus, string);
+        let string_negation = Expr::mk_unary(OpOne::Minus, Expr::from("64"));
 
         assert_eq!(
             interpreter.eval(&string_negation, &env, &mut Base::default()),
             Ok(ExprB::mk_numeric(-64.0))
         );
+
+        let product = Expr::mk_binary(OpTwo::Star, Expr::from("2.5"), Expr::from(2.0));
+
+        assert_eq!(
+            interpreter.eval(&product, &env, &mut Base::default()),
+            Ok(ExprB::mk_numeric(5.0))
+        );
+
+        let invalid = Expr::mk_unary(OpOne::Minus, Expr::from("sixty-four"));
+
+        assert_eq!(
+            interpreter
+                .eval(&invalid, &env, &mut Base::default())
+                .map_err(|e| e.kind),
+            Err(StumbleKind::InvalidConversion)
+        );
+    }
+
+    #[test]
+    fn strict_arithmetic() {
+        let mut interpreter = TreeWalker::default();
+        let env = interpreter.fresh_std_env();
+
+        let string_negation = Expr::mk_unary(OpOne::Minus, Expr::from("64"));
+
+        assert_eq!(
+            interpreter
+                .eval(&string_negation, &env, &mut Base::default())
+                .map_err(|e| e.kind),
+            Err(StumbleKind::TypeError {
+                op: "-".to_owned(),
+                left: None,
+                right: "string"
+            })
+        );
+
+        let comparison = Expr::mk_binary(OpTwo::Lt, Expr::from(1.0), Expr::from("2"));
+
+        assert_eq!(
+            interpreter
+                .eval(&comparison, &env, &mut Base::default())
+                .map_err(|e| e.kind),
+            Err(StumbleKind::TypeError {
+                op: "<".to_owned(),
+                left: Some("float"),
+                right: "string"
+            })
+        );
     }
 
     #[test]
     fn basic_arithmetic() {
-        let interpreter = TreeWalker::default();
-        let env = Env::fresh_std_env();
+        let mut interpreter = TreeWalker::default();
+        let env = interpreter.fresh_std_env();
 
         let a_value = 64.0;
         let b_value = 32.0;
@@ -51,8 +107,8 @@ mod evaluation {
 
     #[test]
     fn basic_string() {
-        let interpreter = TreeWalker::default();
-        let env = Env::fresh_std_env();
+        let mut interpreter = TreeWalker::default();
+        let env = interpreter.fresh_std_env();
 
         let a = Expr::from("a ");
         let b = Expr::from("string");
@@ -67,8 +123,8 @@ mod evaluation {
 
     #[test]
     fn basic_comparison() {
-        let interpreter = TreeWalker::default();
-        let env = Env::fresh_std_env();
+        let mut interpreter = TreeWalker::default();
+        let env = interpreter.fresh_std_env();
 
         let a_value = 64.0;
         let b_value = 32.0;
@@ -88,19 +144,15 @@ mod evaluation {
 
     #[test]
     fn basic_equality() {
-        let interpreter = TreeWalker::default();
-        let env = Env::fresh_std_env();
+        let mut interpreter = TreeWalker::default();
+        let env = interpreter.fresh_std_env();
 
         let a_value = 64.0;
         let b_value = 32.0;
 
         let eq_self = Expr::mk_binary(OpTwo::Eq, Expr::from(a_value), Expr::from(a_value));
 
-        let eq_same = Expr::mk_binary(
-            OpTwo::Eq,
-            Expr::from(Expr::from("a")),
-            Expr::from(Expr::from("a")),
-        );
+        let eq_same = Expr::mk_binary(OpTwo::Eq, Expr::from("a"), Expr::from("a"));
 
         let neq = Expr::mk_binary(OpTwo::Eq, Expr::from(a_value), Expr::from(b_value));
 